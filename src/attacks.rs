@@ -0,0 +1,202 @@
+use std::sync::OnceLock;
+
+use crate::bitboard::Bitboard;
+use crate::{Side, Square};
+
+const KNIGHT_DELTAS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_DELTAS: [(i8, i8); 8] = [(0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1), (-1, 0), (-1, 1)];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+//Squares reached from every square by the given (file, rank) steps, computed at compile time.
+const fn leaper_table(deltas: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let file = (square % 8) as i8;
+        let rank = (square / 8) as i8;
+        let mut attacks = 0u64;
+        let mut i = 0;
+        while i < deltas.len() {
+            let target_file = file + deltas[i].0;
+            let target_rank = rank + deltas[i].1;
+            if target_file >= 0 && target_file < 8 && target_rank >= 0 && target_rank < 8 {
+                attacks |= 1 << (target_rank * 8 + target_file);
+            }
+            i += 1;
+        }
+        table[square] = Bitboard(attacks);
+        square += 1;
+    }
+    table
+}
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_DELTAS);
+const KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_DELTAS);
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [leaper_table(&[(-1, 1), (1, 1)]), leaper_table(&[(-1, -1), (1, -1)])];
+
+pub(crate) fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square.index()]
+}
+
+pub(crate) fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+//Squares a pawn of the given side attacks diagonally, which is not where it can push to.
+pub(crate) fn pawn_attacks(square: Square, side: Side) -> Bitboard {
+    PAWN_ATTACKS[side as usize][square.index()]
+}
+
+//Attacks found by walking each ray, only used to fill the magic tables.
+fn sliding_attacks(square: Square, occupied: Bitboard, directions: &[(i8, i8)]) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    for &(file_delta, rank_delta) in directions {
+        let mut current = square;
+        while let Some(next) = current.offset(file_delta, rank_delta) {
+            attacks |= Bitboard::from_square(next);
+            if occupied.contains(next) {
+                break;
+            }
+            current = next;
+        }
+    }
+    attacks
+}
+
+//Squares whose occupancy can change the attacks from square. The last square of each ray is left out since a piece there is attacked either way.
+fn relevant_occupancy(square: Square, directions: &[(i8, i8)]) -> Bitboard {
+    let mut mask = Bitboard::EMPTY;
+    for &(file_delta, rank_delta) in directions {
+        let mut current = square;
+        while let Some(next) = current.offset(file_delta, rank_delta) {
+            if next.offset(file_delta, rank_delta).is_none() {
+                break;
+            }
+            mask |= Bitboard::from_square(next);
+            current = next;
+        }
+    }
+    mask
+}
+
+//Multipliers that map every occupancy of a square's relevant squares to its own slot in the attack table, found by trying sparse random numbers until one gave no collisions.
+const ROOK_MAGICS: [u64; 64] = [
+    0x2080002080400010, 0x00c0002001401000, 0x2100110008402002, 0x0880080081041000,
+    0x0200020020041008, 0x2300040008010012, 0x0c00283004008201, 0x0180010000407a80,
+    0x0168800080400020, 0x0010400040201000, 0x1001002001001048, 0x1001002408100100,
+    0x0801000408010012, 0x4001000209000400, 0x08a20004c8020001, 0x2002801145002280,
+    0x0080860021004200, 0x001000c009402002, 0x00b0002004002800, 0x100a808010020800,
+    0x8101010008000410, 0x0244008002000480, 0x0000040010810208, 0x2000020000448534,
+    0x4104400480008033, 0x0000810100204000, 0x0440430900200010, 0x4600240900100100,
+    0x0060080080040080, 0x0001000300080400, 0x0004084400011002, 0x0023040200008041,
+    0x0580050043002080, 0x0400804002802008, 0x0001002001004010, 0x1000200901001000,
+    0x4410800801800c00, 0xa012003806001004, 0x0020100104008802, 0x0004808402000041,
+    0x0010400170898000, 0x0080500020004004, 0x1040408012020020, 0x8010040008004040,
+    0x2001080100110004, 0x0000020004008080, 0x0021010810040002, 0x0800008c43020024,
+    0x0000800021005100, 0x0070201040008080, 0x0000d04282006a00, 0x0010014400080240,
+    0x0001080110050100, 0x0012000810240600, 0x0402000801040200, 0x028100108a004100,
+    0x0050800300102045, 0x8208210040120882, 0x8010600101183441, 0x020b000910006045,
+    0x0241001002480005, 0x0081000400880241, 0x0000009008024124, 0x0048122980410402,
+];
+
+const BISHOP_MAGICS: [u64; 64] = [
+    0x0848020822040013, 0x8010a40085821200, 0x0008008430840822, 0x0808048108040000,
+    0x1304042100008104, 0x5001012010204023, 0x81048801b8200420, 0x200a008084012000,
+    0x0040102001042084, 0x840a505042428020, 0x0000700102202920, 0x44101c0c10800002,
+    0x0040040422000000, 0x0180020802090202, 0x4020020811041202, 0x000104308c042000,
+    0x4140661002424400, 0x0028012008010460, 0x0188062102002a00, 0x0014004840102008,
+    0x0105000290400002, 0x8001022200410400, 0x104a041918013446, 0x008a000082008238,
+    0x04a0060008100430, 0x0008220008820801, 0x2508041208005010, 0x4008080200202020,
+    0x2441001013004000, 0x0030008060407000, 0x4008108000420800, 0x0012021050290100,
+    0x0210080482200500, 0xcc01112048100480, 0x0020402806500440, 0x00048e0080580080,
+    0x0040102020020080, 0x0028010440080807, 0x4601041108008800, 0x8040810e04104200,
+    0x901210110400088a, 0xa003080212081050, 0x00c1004048401004, 0x900000a014400800,
+    0x0008021040405401, 0x4020008206002090, 0x0004190424030100, 0x0424008a02026250,
+    0x8004088250900040, 0x1c00430088a04200, 0x0001020094040001, 0x8040210020880061,
+    0x2010040450442032, 0x0800840850044001, 0x0004040802140004, 0x0004080a04222020,
+    0x8088802110022000, 0x1081a10416114400, 0x0205010a24060820, 0x0000000720411080,
+    0x1008000208430400, 0x580c026028810840, 0x802020441020a110, 0x12c0022401020018,
+];
+
+//Maps the occupied squares within "mask" to an index into the attack table with a multiplication and a shift.
+#[derive(Copy, Clone, Default)]
+struct Magic {
+    mask: Bitboard,
+    magic: u64,
+    shift: u32,
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: Bitboard) -> usize {
+        self.offset + ((occupied & self.mask).0.wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+struct SlidingTables {
+    rook: [Magic; 64],
+    bishop: [Magic; 64],
+    attacks: Vec<Bitboard>,
+}
+
+//Appends the attacks for every occupancy of every square to "attacks" and returns how to index them.
+fn fill_table(directions: &[(i8, i8)], magics: &[u64; 64], attacks: &mut Vec<Bitboard>) -> [Magic; 64] {
+    let mut table = [Magic::default(); 64];
+    for square in Square::all() {
+        let mask = relevant_occupancy(square, directions);
+        let bits = mask.0.count_ones();
+        let magic = Magic { mask, magic: magics[square.index()], shift: 64 - bits, offset: attacks.len() };
+        attacks.resize(attacks.len() + (1 << bits), Bitboard::EMPTY);
+
+        //every subset of the mask, enumerated with the carry-rippler trick
+        let mut subset = Bitboard::EMPTY;
+        loop {
+            attacks[magic.index(subset)] = sliding_attacks(square, subset, directions);
+            subset = Bitboard(subset.0.wrapping_sub(mask.0) & mask.0);
+            if subset.is_empty() {
+                break;
+            }
+        }
+        table[square.index()] = magic;
+    }
+    table
+}
+
+//Built the first time a sliding piece is looked up.
+fn sliding_tables() -> &'static SlidingTables {
+    static TABLES: OnceLock<SlidingTables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut attacks = Vec::new();
+        let rook = fill_table(&ROOK_DIRECTIONS, &ROOK_MAGICS, &mut attacks);
+        let bishop = fill_table(&BISHOP_DIRECTIONS, &BISHOP_MAGICS, &mut attacks);
+        SlidingTables { rook, bishop, attacks }
+    })
+}
+
+//Squares a rook attacks, up to and including the first occupied square in each direction.
+pub(crate) fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_tables();
+    tables.attacks[tables.rook[square.index()].index(occupied)]
+}
+
+pub(crate) fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_tables();
+    tables.attacks[tables.bishop[square.index()].index(occupied)]
+}
+
+pub(crate) fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+//Squares strictly between a and b if they share a rank, file or diagonal, otherwise none.
+pub(crate) fn between(a: Square, b: Square) -> Bitboard {
+    let (from_a, from_b) = (Bitboard::from_square(a), Bitboard::from_square(b));
+    if rook_attacks(a, Bitboard::EMPTY).contains(b) {
+        rook_attacks(a, from_b) & rook_attacks(b, from_a)
+    } else if bishop_attacks(a, Bitboard::EMPTY).contains(b) {
+        bishop_attacks(a, from_b) & bishop_attacks(b, from_a)
+    } else {
+        Bitboard::EMPTY
+    }
+}
@@ -0,0 +1,175 @@
+use crate::attacks::{between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks};
+use crate::bitboard::Bitboard;
+use crate::zobrist::piece_key;
+use crate::{Piece, Side, Square};
+
+const PIECES: [Piece; 6] = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn];
+
+//Piece placement as one bitboard per piece type and one per side. It is Copy and small, so moves can be tried on a copy.
+//"key" is the Zobrist key of the pieces alone, updated whenever a square changes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub(crate) struct Board {
+    pieces: [Bitboard; 6],
+    sides: [Bitboard; 2],
+    key: u64,
+}
+
+impl Board {
+    pub(crate) fn empty() -> Self {
+        Board { pieces: [Bitboard::EMPTY; 6], sides: [Bitboard::EMPTY; 2], key: 0 }
+    }
+
+    pub(crate) fn from_arrays(pieces: &[Piece; 64], sides: &[Side; 64]) -> Self {
+        let mut board = Board::empty();
+        for square in Square::all() {
+            board.set(square, pieces[square.index()], sides[square.index()]);
+        }
+        board
+    }
+
+    pub(crate) fn pieces_array(&self) -> [Piece; 64] {
+        let mut pieces = [Piece::None; 64];
+        for square in Square::all() {
+            pieces[square.index()] = self.piece_at(square);
+        }
+        pieces
+    }
+
+    pub(crate) fn sides_array(&self) -> [Side; 64] {
+        let mut sides = [Side::None; 64];
+        for square in Square::all() {
+            sides[square.index()] = self.side_at(square);
+        }
+        sides
+    }
+
+    pub(crate) fn piece_at(&self, square: Square) -> Piece {
+        PIECES.into_iter().find(|&piece| self.pieces[piece as usize].contains(square)).unwrap_or(Piece::None)
+    }
+
+    pub(crate) fn side_at(&self, square: Square) -> Side {
+        if self.sides[Side::White as usize].contains(square) {
+            Side::White
+        } else if self.sides[Side::Black as usize].contains(square) {
+            Side::Black
+        } else {
+            Side::None
+        }
+    }
+
+    //Puts a piece on the square, replacing whatever was there. Piece::None or Side::None leaves the square empty.
+    pub(crate) fn set(&mut self, square: Square, piece: Piece, side: Side) {
+        self.clear(square);
+        if piece != Piece::None && side != Side::None {
+            self.pieces[piece as usize] |= Bitboard::from_square(square);
+            self.sides[side as usize] |= Bitboard::from_square(square);
+            self.key ^= piece_key(piece, side, square);
+        }
+    }
+
+    pub(crate) fn clear(&mut self, square: Square) {
+        let piece = self.piece_at(square);
+        if piece != Piece::None {
+            self.key ^= piece_key(piece, self.side_at(square), square);
+        }
+        let mask = !Bitboard::from_square(square);
+        for bitboard in self.pieces.iter_mut().chain(self.sides.iter_mut()) {
+            *bitboard &= mask;
+        }
+    }
+
+    pub(crate) fn key(&self) -> u64 {
+        self.key
+    }
+
+    pub(crate) fn pieces(&self, piece: Piece, side: Side) -> Bitboard {
+        self.pieces[piece as usize] & self.sides[side as usize]
+    }
+
+    //Pieces of the type on both sides.
+    pub(crate) fn pieces_of_type(&self, piece: Piece) -> Bitboard {
+        self.pieces[piece as usize]
+    }
+
+    pub(crate) fn side_pieces(&self, side: Side) -> Bitboard {
+        self.sides[side as usize]
+    }
+
+    pub(crate) fn occupied(&self) -> Bitboard {
+        self.sides[Side::White as usize] | self.sides[Side::Black as usize]
+    }
+
+    pub(crate) fn king_square(&self, side: Side) -> Option<Square> {
+        self.pieces(Piece::King, side).first()
+    }
+
+    //Pieces of side "by" attacking the square, with sliding pieces blocked by "occupied".
+    pub(crate) fn attackers_to(&self, square: Square, by: Side, occupied: Bitboard) -> Bitboard {
+        let queens = self.pieces(Piece::Queen, by);
+        (pawn_attacks(square, !by) & self.pieces(Piece::Pawn, by))
+            | (knight_attacks(square) & self.pieces(Piece::Knight, by))
+            | (king_attacks(square) & self.pieces(Piece::King, by))
+            | (rook_attacks(square, occupied) & (self.pieces(Piece::Rook, by) | queens))
+            | (bishop_attacks(square, occupied) & (self.pieces(Piece::Bishop, by) | queens))
+    }
+
+    //Squares the piece on the square attacks, empty if there is none. Pawns attack diagonally forward, not where they push to.
+    pub(crate) fn attacks_from(&self, square: Square) -> Bitboard {
+        let occupied = self.occupied();
+        match self.piece_at(square) {
+            Piece::Pawn => pawn_attacks(square, self.side_at(square)),
+            Piece::Knight => knight_attacks(square),
+            Piece::Bishop => bishop_attacks(square, occupied),
+            Piece::Rook => rook_attacks(square, occupied),
+            Piece::Queen => rook_attacks(square, occupied) | bishop_attacks(square, occupied),
+            Piece::King => king_attacks(square),
+            Piece::None => Bitboard::EMPTY,
+        }
+    }
+
+    pub(crate) fn is_attacked(&self, square: Square, by: Side) -> bool {
+        !self.attackers_to(square, by, self.occupied()).is_empty()
+    }
+
+    pub(crate) fn is_in_check(&self, side: Side) -> bool {
+        self.king_square(side).is_some_and(|king| self.is_attacked(king, !side))
+    }
+
+    //Pieces of side that shield their king from an enemy rook, bishop or queen, each with the squares it can still move to: the line up to and including the pinning piece.
+    pub(crate) fn pins(&self, side: Side) -> Vec<(Square, Bitboard)> {
+        let mut pins = Vec::new();
+        let Some(king) = self.king_square(side) else {
+            return pins;
+        };
+        let queens = self.pieces(Piece::Queen, !side);
+        let snipers = (rook_attacks(king, Bitboard::EMPTY) & (self.pieces(Piece::Rook, !side) | queens))
+            | (bishop_attacks(king, Bitboard::EMPTY) & (self.pieces(Piece::Bishop, !side) | queens));
+        for sniper in snipers {
+            let line = between(king, sniper);
+            let blockers = line & self.occupied();
+            if blockers.count() == 1 && !(blockers & self.side_pieces(side)).is_empty() {
+                let pinned = blockers.first().expect("one blocker");
+                pins.push((pinned, line | Bitboard::from_square(sniper)));
+            }
+        }
+        pins
+    }
+
+    //Moves the piece on origin to target. A pawn moving to en_passant captures the pawn beside it, and a king moving two files takes the rook along.
+    pub(crate) fn apply_move(&mut self, origin: Square, target: Square, promotion: Option<Piece>, en_passant: Option<Square>) {
+        let piece = self.piece_at(origin);
+        let side = self.side_at(origin);
+        if piece == Piece::Pawn && en_passant == Some(target) {
+            self.clear(Square::new(target.file(), origin.rank()).expect("square beside the pawn"));
+        }
+        if piece == Piece::King && origin.file().abs_diff(target.file()) == 2 {
+            let (rook_file, rook_target_file) = if target.file() > origin.file() { (7, 5) } else { (0, 3) };
+            let rook_origin = Square::new(rook_file, origin.rank()).expect("corner square");
+            let rook_target = Square::new(rook_target_file, origin.rank()).expect("square beside the king");
+            self.clear(rook_origin);
+            self.set(rook_target, Piece::Rook, side);
+        }
+        self.clear(origin);
+        self.set(target, promotion.unwrap_or(piece), side);
+    }
+}
@@ -0,0 +1,90 @@
+use std::ops::{BitAnd, BitOr, Not};
+
+use crate::{Side, Square};
+
+//The castling rights still held, a set of up to four flags. Rights are lost for good when the king or the rook moves, or when the rook is captured.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct CastlingRights(u8);
+
+impl CastlingRights {
+    pub const NONE: CastlingRights = CastlingRights(0);
+    pub const WHITE_KINGSIDE: CastlingRights = CastlingRights(1);
+    pub const WHITE_QUEENSIDE: CastlingRights = CastlingRights(2);
+    pub const BLACK_KINGSIDE: CastlingRights = CastlingRights(4);
+    pub const BLACK_QUEENSIDE: CastlingRights = CastlingRights(8);
+    pub const ALL: CastlingRights = CastlingRights(15);
+
+    pub fn kingside(side: Side) -> CastlingRights {
+        match side {
+            Side::White => Self::WHITE_KINGSIDE,
+            Side::Black => Self::BLACK_KINGSIDE,
+            Side::None => Self::NONE,
+        }
+    }
+
+    pub fn queenside(side: Side) -> CastlingRights {
+        match side {
+            Side::White => Self::WHITE_QUEENSIDE,
+            Side::Black => Self::BLACK_QUEENSIDE,
+            Side::None => Self::NONE,
+        }
+    }
+
+    pub fn contains(self, rights: CastlingRights) -> bool {
+        self.0 & rights.0 == rights.0
+    }
+
+    pub fn insert(&mut self, rights: CastlingRights) {
+        self.0 |= rights.0;
+    }
+
+    pub fn remove(&mut self, rights: CastlingRights) {
+        self.0 &= !rights.0;
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    //One bit per right, in the order K, Q, k, q.
+    pub(crate) fn bits(self) -> u8 {
+        self.0
+    }
+
+    //Rights that are lost when a piece moves from or to the square: the king's home square loses both, a rook's home square loses that side.
+    pub(crate) fn lost_on(square: Square) -> CastlingRights {
+        match square.index() {
+            0 => Self::WHITE_QUEENSIDE,
+            4 => Self::WHITE_KINGSIDE | Self::WHITE_QUEENSIDE,
+            7 => Self::WHITE_KINGSIDE,
+            56 => Self::BLACK_QUEENSIDE,
+            60 => Self::BLACK_KINGSIDE | Self::BLACK_QUEENSIDE,
+            63 => Self::BLACK_KINGSIDE,
+            _ => Self::NONE,
+        }
+    }
+}
+
+impl BitOr for CastlingRights {
+    type Output = CastlingRights;
+
+    fn bitor(self, rhs: CastlingRights) -> CastlingRights {
+        CastlingRights(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for CastlingRights {
+    type Output = CastlingRights;
+
+    fn bitand(self, rhs: CastlingRights) -> CastlingRights {
+        CastlingRights(self.0 & rhs.0)
+    }
+}
+
+impl Not for CastlingRights {
+    type Output = CastlingRights;
+
+    fn not(self) -> CastlingRights {
+        CastlingRights(!self.0 & Self::ALL.0)
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::Side;
+
+//Where the clock reads the time from. Only differences between readings matter, so "now" can count from any starting point.
+pub trait TimeSource: Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+//Real time, counted from when the source was created.
+pub struct SystemTimeSource {
+    start: Instant,
+}
+
+impl SystemTimeSource {
+    pub fn new() -> Self {
+        SystemTimeSource { start: Instant::now() }
+    }
+}
+
+impl Default for SystemTimeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+//Time that only moves when advance is called, for tests and for replaying games with recorded move times.
+#[derive(Default)]
+pub struct ManualTimeSource {
+    nanos: AtomicU64,
+}
+
+impl ManualTimeSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl TimeSource for ManualTimeSource {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+//What happens to the time of each move.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Increment {
+    None,
+    //the time is added after every move
+    Fischer(Duration),
+    //after every move the time used is given back, but at most this much
+    Bronstein(Duration),
+    //the clock only starts counting down after this much of every move
+    SimpleDelay(Duration),
+}
+
+//"time" for "moves" moves, or for the rest of the game if "moves" is None.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TimePeriod {
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub increment: Increment,
+}
+
+//One or more periods, played in order. If the last period has a move count it is repeated.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TimeControl {
+    pub periods: Vec<TimePeriod>,
+}
+
+impl TimeControl {
+    pub fn new(periods: Vec<TimePeriod>) -> Self {
+        assert!(!periods.is_empty(), "a time control needs at least one period");
+        TimeControl { periods }
+    }
+
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::with_increment(time, Increment::None)
+    }
+
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::with_increment(time, Increment::Fischer(increment))
+    }
+
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::with_increment(time, Increment::Bronstein(delay))
+    }
+
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::with_increment(time, Increment::SimpleDelay(delay))
+    }
+
+    fn with_increment(time: Duration, increment: Increment) -> Self {
+        TimeControl { periods: vec![TimePeriod { moves: None, time, increment }] }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SideClock {
+    remaining: Duration,
+    period: usize,
+    moves_in_period: u32,
+}
+
+//A chess clock for both sides. It is attached to a game with Game::set_clock, after which every move stops the mover's time and starts the opponent's.
+#[derive(Clone)]
+pub struct Clock {
+    control: TimeControl,
+    source: Arc<dyn TimeSource>,
+    sides: [SideClock; 2],
+    running: Side,
+    turn_start: Duration,
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clock")
+            .field("control", &self.control)
+            .field("white", &self.remaining(Side::White))
+            .field("black", &self.remaining(Side::Black))
+            .field("running", &self.running)
+            .finish()
+    }
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        Self::with_time_source(control, Arc::new(SystemTimeSource::new()))
+    }
+
+    pub fn with_time_source(control: TimeControl, source: Arc<dyn TimeSource>) -> Self {
+        let side = SideClock { remaining: control.periods[0].time, period: 0, moves_in_period: 0 };
+        let turn_start = source.now();
+        Clock { control, source, sides: [side; 2], running: Side::None, turn_start }
+    }
+
+    pub fn time_control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    //The side whose time is running, Side::None before the game starts and after it ends.
+    pub fn running(&self) -> Side {
+        self.running
+    }
+
+    //Time left for side, counting the running move. Zero for Side::None.
+    pub fn remaining(&self, side: Side) -> Duration {
+        if side == Side::None {
+            return Duration::ZERO;
+        }
+        let clock = &self.sides[side as usize];
+        if side != self.running {
+            return clock.remaining;
+        }
+        clock.remaining.saturating_sub(self.charged_time(clock, self.elapsed()))
+    }
+
+    pub fn is_flagged(&self, side: Side) -> bool {
+        if side == Side::None {
+            return false;
+        }
+        let clock = &self.sides[side as usize];
+        side == self.running && self.charged_time(clock, self.elapsed()) > clock.remaining
+    }
+
+    fn period(&self, clock: &SideClock) -> &TimePeriod {
+        &self.control.periods[clock.period]
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.source.now().saturating_sub(self.turn_start)
+    }
+
+    //Part of the time spent on the running move that counts against the clock, which leaves out a simple delay.
+    fn charged_time(&self, clock: &SideClock, elapsed: Duration) -> Duration {
+        match self.period(clock).increment {
+            Increment::SimpleDelay(delay) => elapsed.saturating_sub(delay),
+            _ => elapsed,
+        }
+    }
+
+    pub(crate) fn start(&mut self, side: Side) {
+        self.running = side;
+        self.turn_start = self.source.now();
+    }
+
+    //Time, period and move count of both sides, for Game to save before a move and put back with restore when it is undone.
+    pub(crate) fn sides(&self) -> [SideClock; 2] {
+        self.sides
+    }
+
+    pub(crate) fn restore(&mut self, sides: [SideClock; 2]) {
+        self.sides = sides;
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.running = Side::None;
+    }
+
+    //Ends the move of side and starts the opponent's time. Returns false without changing anything if side ran out of time.
+    pub(crate) fn press(&mut self, side: Side) -> bool {
+        if self.running != side {
+            return true;
+        }
+        let now = self.source.now();
+        let elapsed = now.saturating_sub(self.turn_start);
+        let mut clock = self.sides[side as usize];
+        let period = *self.period(&clock);
+        let charged = self.charged_time(&clock, elapsed);
+        if charged > clock.remaining {
+            return false;
+        }
+        clock.remaining -= charged;
+        match period.increment {
+            Increment::Fischer(increment) => clock.remaining += increment,
+            Increment::Bronstein(delay) => clock.remaining += elapsed.min(delay),
+            Increment::None | Increment::SimpleDelay(_) => {}
+        }
+        clock.moves_in_period += 1;
+        if period.moves == Some(clock.moves_in_period) {
+            clock.period = (clock.period + 1).min(self.control.periods.len() - 1);
+            clock.moves_in_period = 0;
+            clock.remaining += self.control.periods[clock.period].time;
+        }
+        self.sides[side as usize] = clock;
+        self.running = !side;
+        self.turn_start = now;
+        true
+    }
+}
@@ -0,0 +1,277 @@
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+use crate::{BoardState, Game, Move, Piece, PieceValues, Side};
+
+const MAX_DEPTH: u32 = 64;
+const MATE: i32 = 1_000_000;
+//scores further from 0 than this are mates, MATE minus the plies until the mate
+const MATE_BOUND: i32 = MATE - 1000;
+//the time limit is only checked every this many nodes, reading the time costs more than a node
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+const PIECES: [Piece; 6] = [Piece::King, Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight, Piece::Pawn];
+
+//When to stop searching. Every limit that is set applies, and with none set the search goes on to depth 64 or until a mate is found.
+//The first iteration, depth 1, is always finished so there is a move to return.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        SearchLimits { depth: Some(depth), ..Self::default() }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits { nodes: Some(nodes), ..Self::default() }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        SearchLimits { time: Some(time), ..Self::default() }
+    }
+}
+
+//Evaluation from the side to move's point of view. Mate(n) is a mate in n moves, negative if the side to move gets mated.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+impl Score {
+    fn from_search(score: i32) -> Score {
+        if score > MATE_BOUND {
+            Score::Mate((MATE - score + 1) / 2)
+        } else if score < -MATE_BOUND {
+            Score::Mate(-(MATE + score) / 2)
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+//Outcome of the deepest iteration that was finished. "best_move" is None if the game is already over.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: Score,
+    //the best move followed by the best replies of both sides
+    pub pv: Vec<Move>,
+    pub depth: u32,
+    pub nodes: u64,
+}
+
+//Negamax alpha-beta search with iterative deepening and a quiescence search of captures. It plays on a copy of the game,
+//so repetitions and the other draws BoardState knows about count in the search.
+pub struct Engine {
+    piece_values: PieceValues,
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    //best line found from each ply of the current iteration, the one at ply 0 is the principal variation
+    pv_table: Vec<Vec<Move>>,
+    //principal variation of the last finished iteration, which is searched first
+    previous_pv: Vec<Move>,
+    //set while the first iteration runs, which has to finish
+    must_finish: bool,
+    stopped: bool,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_piece_values(PieceValues::default())
+    }
+
+    pub fn with_piece_values(piece_values: PieceValues) -> Self {
+        Engine {
+            piece_values,
+            limits: SearchLimits::default(),
+            start: Instant::now(),
+            nodes: 0,
+            pv_table: vec![Vec::new(); MAX_DEPTH as usize + 1],
+            previous_pv: Vec::new(),
+            must_finish: false,
+            stopped: false,
+        }
+    }
+
+    pub fn piece_values(&self) -> &PieceValues {
+        &self.piece_values
+    }
+
+    //Searches the position of the game for the side to move. The game itself isn't changed, and its clock isn't used.
+    pub fn search(&mut self, game: &Game, limits: SearchLimits) -> SearchResult {
+        let mut game = game.clone();
+        game.clock = None;
+        self.limits = limits;
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.previous_pv.clear();
+        self.stopped = false;
+
+        let mut result = SearchResult { best_move: None, score: Score::Centipawns(0), pv: Vec::new(), depth: 0, nodes: 0 };
+        if game.board_state().is_game_over() {
+            if let BoardState::CheckMated(_) = game.board_state() {
+                result.score = Score::Mate(0);
+            }
+            return result;
+        }
+        let max_depth = limits.depth.unwrap_or(MAX_DEPTH).clamp(1, MAX_DEPTH);
+        for depth in 1..=max_depth {
+            self.must_finish = depth == 1;
+            let score = self.negamax(&mut game, depth, 0, -MATE, MATE, true);
+            if self.stopped {
+                break;
+            }
+            let pv = self.pv_table[0].clone();
+            self.previous_pv.clone_from(&pv);
+            result = SearchResult { best_move: pv.first().copied(), score: Score::from_search(score), pv, depth, nodes: self.nodes };
+            //iterative deepening finds the shortest mate first
+            if score.abs() > MATE_BOUND || self.out_of_time() {
+                break;
+            }
+        }
+        result.nodes = self.nodes;
+        result
+    }
+
+    //Best score for the side to move "depth" plies deep, with the line leading to it left in pv_table[ply]. "on_pv" is set while
+    //following the last iteration's principal variation, whose next move is searched first.
+    fn negamax(&mut self, game: &mut Game, depth: u32, ply: usize, mut alpha: i32, beta: i32, on_pv: bool) -> i32 {
+        self.pv_table[ply].clear();
+        if let Some(score) = terminal_score(game, ply) {
+            return score;
+        }
+        if depth == 0 {
+            return self.quiescence(game, ply, alpha, beta);
+        }
+        let hint = if on_pv { self.previous_pv.get(ply).copied() } else { None };
+        let mut moves = game.legal_moves();
+        self.order_moves(game, &mut moves, hint);
+        for mv in moves {
+            self.make_move(game, mv);
+            let score = -self.negamax(game, depth - 1, ply + 1, -beta, -alpha, on_pv && Some(mv) == hint);
+            game.undo_move();
+            if self.stopped {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                let line = std::mem::take(&mut self.pv_table[ply + 1]);
+                self.pv_table[ply].clear();
+                self.pv_table[ply].push(mv);
+                self.pv_table[ply].extend_from_slice(&line);
+                self.pv_table[ply + 1] = line;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        alpha
+    }
+
+    //Only captures that don't lose material are searched, and the side to move can stand pat instead of capturing.
+    fn quiescence(&mut self, game: &mut Game, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        if let Some(score) = terminal_score(game, ply) {
+            return score;
+        }
+        let stand_pat = self.evaluate(game);
+        if stand_pat >= beta {
+            return beta;
+        }
+        alpha = alpha.max(stand_pat);
+        let mut captures: Vec<(i32, Move)> = game
+            .legal_captures()
+            .into_iter()
+            .map(|mv| (game.see_with_values(mv, &self.piece_values), mv))
+            .filter(|&(see, _)| see >= 0)
+            .collect();
+        captures.sort_by_key(|&(see, _)| Reverse(see));
+        for (_, mv) in captures {
+            self.make_move(game, mv);
+            let score = -self.quiescence(game, ply + 1, -beta, -alpha);
+            game.undo_move();
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    //The last iteration's move first, then captures and promotions by how much material they win, then quiet moves.
+    fn order_moves(&self, game: &Game, moves: &mut [Move], hint: Option<Move>) {
+        moves.sort_by_cached_key(|&mv| {
+            if Some(mv) == hint {
+                Reverse(i32::MAX)
+            } else if mv.is_capture() || mv.promotion.is_some() {
+                Reverse(MATE + game.see_with_values(mv, &self.piece_values))
+            } else {
+                Reverse(0)
+            }
+        });
+    }
+
+    fn make_move(&mut self, game: &mut Game, mv: Move) {
+        game.do_move(mv);
+        self.nodes += 1;
+        if self.must_finish {
+            return;
+        }
+        if self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes) || (self.nodes.is_multiple_of(TIME_CHECK_INTERVAL) && self.out_of_time()) {
+            self.stopped = true;
+        }
+    }
+
+    fn out_of_time(&self) -> bool {
+        self.limits.time.is_some_and(|time| self.start.elapsed() >= time)
+    }
+
+    //Material plus small bonuses for advanced pawns and for knights and bishops near the centre, from the side to move's point of view.
+    fn evaluate(&self, game: &Game) -> i32 {
+        let board = &game.position().board;
+        let mut score = 0;
+        for side in [Side::White, Side::Black] {
+            let sign = if side == game.curr_turn() { 1 } else { -1 };
+            for piece in PIECES {
+                for square in board.pieces(piece, side) {
+                    let bonus = match piece {
+                        Piece::Pawn => 5 * if side == Side::White { square.rank() as i32 - 1 } else { 6 - square.rank() as i32 },
+                        Piece::Knight | Piece::Bishop => {
+                            let from_centre = (2 * square.file() as i32 - 7).abs() + (2 * square.rank() as i32 - 7).abs();
+                            2 * (14 - from_centre)
+                        }
+                        _ => 0,
+                    };
+                    score += sign * (self.piece_values.value(piece) + bonus);
+                }
+            }
+        }
+        score
+    }
+}
+
+//Score of a position where the search stops: mated, drawn by BoardState, or a repetition or 50 moves without a capture or pawn move inside the search.
+fn terminal_score(game: &Game, ply: usize) -> Option<i32> {
+    if ply > 0 && (game.repetition_count() > 1 || game.halfmove_clock() >= 100) {
+        return Some(0);
+    }
+    match game.board_state() {
+        BoardState::CheckMated(_) => Some(-(MATE - ply as i32)),
+        state if state.is_game_over() => Some(0),
+        _ => None,
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+
+use crate::board::Board;
+use crate::{CastlingRights, Game, Piece, Position, Side, Square};
+
+pub const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const CASTLING_LETTERS: [(char, CastlingRights); 4] = [
+    ('K', CastlingRights::WHITE_KINGSIDE),
+    ('Q', CastlingRights::WHITE_QUEENSIDE),
+    ('k', CastlingRights::BLACK_KINGSIDE),
+    ('q', CastlingRights::BLACK_QUEENSIDE),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    WrongFieldCount(usize),
+    WrongRankCount(usize),
+    InvalidRankLength(usize),
+    InvalidPiece(char),
+    InvalidKingCount(Side),
+    PawnOnBackRank(Square),
+    InvalidSideToMove(String),
+    SideNotToMoveInCheck(Side),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::WrongFieldCount(count) => write!(f, "expected 4 or 6 fields, found {}", count),
+            FenError::WrongRankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+            FenError::InvalidRankLength(rank) => write!(f, "rank {} does not contain exactly 8 squares", rank),
+            FenError::InvalidPiece(c) => write!(f, "invalid piece character '{}'", c),
+            FenError::InvalidKingCount(side) => write!(f, "{:?} must have exactly one king", side),
+            FenError::PawnOnBackRank(square) => write!(f, "pawn on {}, pawns can't stand on the first or eighth rank", square),
+            FenError::InvalidSideToMove(field) => write!(f, "invalid side to move '{}'", field),
+            FenError::SideNotToMoveInCheck(side) => write!(f, "{:?} is in check but it is not their turn", side),
+            FenError::InvalidCastling(field) => write!(f, "invalid castling rights '{}'", field),
+            FenError::InvalidEnPassant(field) => write!(f, "invalid en passant square '{}'", field),
+            FenError::InvalidHalfmoveClock(field) => write!(f, "invalid halfmove clock '{}'", field),
+            FenError::InvalidFullmoveNumber(field) => write!(f, "invalid fullmove number '{}'", field),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+fn piece_from_char(c: char) -> Option<(Piece, Side)> {
+    let side = if c.is_ascii_uppercase() { Side::White } else { Side::Black };
+    let piece = match c.to_ascii_lowercase() {
+        'k' => Piece::King,
+        'q' => Piece::Queen,
+        'r' => Piece::Rook,
+        'b' => Piece::Bishop,
+        'n' => Piece::Knight,
+        'p' => Piece::Pawn,
+        _ => return None,
+    };
+    Some((piece, side))
+}
+
+fn piece_to_char(piece: Piece, side: Side) -> char {
+    let c = match piece {
+        Piece::King => 'k',
+        Piece::Queen => 'q',
+        Piece::Rook => 'r',
+        Piece::Bishop => 'b',
+        Piece::Knight => 'n',
+        Piece::Pawn => 'p',
+        Piece::None => '1',
+    };
+    if side == Side::White { c.to_ascii_uppercase() } else { c }
+}
+
+impl Position {
+    //Builds a position from a FEN string. The halfmove clock and fullmove number may be left out, in which case they default to 0 and 1.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() != 4 && fields.len() != 6 {
+            return Err(FenError::WrongFieldCount(fields.len()));
+        }
+
+        let mut position = Position::new();
+        position.board = Board::empty();
+
+        let ranks: Vec<&str> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::WrongRankCount(ranks.len()));
+        }
+        let mut white_kings = Vec::new();
+        let mut black_kings = Vec::new();
+        for (i, rank_str) in ranks.iter().enumerate() {
+            let rank = 7 - i;
+            let mut file = 0;
+            for c in rank_str.chars() {
+                if let Some(empty) = c.to_digit(10).filter(|n| (1..=8).contains(n)) {
+                    file += empty as usize;
+                    continue;
+                }
+                let (piece, side) = piece_from_char(c).ok_or(FenError::InvalidPiece(c))?;
+                if file >= 8 {
+                    return Err(FenError::InvalidRankLength(rank + 1));
+                }
+                let square = Square::new(file as u8, rank as u8).expect("rank and file are checked above");
+                if piece == Piece::Pawn && (rank == 0 || rank == 7) {
+                    return Err(FenError::PawnOnBackRank(square));
+                }
+                position.board.set(square, piece, side);
+                if piece == Piece::King {
+                    if side == Side::White { white_kings.push(square) } else { black_kings.push(square) }
+                }
+                file += 1;
+            }
+            if file != 8 {
+                return Err(FenError::InvalidRankLength(rank + 1));
+            }
+        }
+        if white_kings.len() != 1 {
+            return Err(FenError::InvalidKingCount(Side::White));
+        }
+        if black_kings.len() != 1 {
+            return Err(FenError::InvalidKingCount(Side::Black));
+        }
+
+        position.curr_turn = match fields[1] {
+            "w" => Side::White,
+            "b" => Side::Black,
+            other => return Err(FenError::InvalidSideToMove(other.to_string())),
+        };
+        // the side that just moved can't have left its king in check
+        if position.is_checked(!position.curr_turn) {
+            return Err(FenError::SideNotToMoveInCheck(!position.curr_turn));
+        }
+
+        let castling = fields[2];
+        let invalid = || FenError::InvalidCastling(castling.to_string());
+        let mut rights = CastlingRights::NONE;
+        if castling != "-" {
+            // each letter at most once and in the order KQkq, so "KKK" or "qK" are rejected
+            let mut letters = CASTLING_LETTERS.iter();
+            for c in castling.chars() {
+                let &(_, right) = letters.find(|(letter, _)| *letter == c).ok_or_else(invalid)?;
+                rights.insert(right);
+            }
+            if rights.is_empty() {
+                return Err(invalid());
+            }
+        }
+        position.set_castling_rights(rights);
+        // set_castling_rights drops rights whose king or rook isn't on its starting square
+        if position.castling_rights != rights {
+            return Err(invalid());
+        }
+
+        let en_passant = fields[3];
+        if en_passant != "-" {
+            let invalid = || FenError::InvalidEnPassant(en_passant.to_string());
+            let square: Square = en_passant.parse().map_err(|_| invalid())?;
+            // the pawn that double stepped has to be on the square in front of it
+            let (direction, pushed_side) = match position.curr_turn {
+                Side::Black if square.rank() == 2 => (1, Side::White),
+                Side::White if square.rank() == 5 => (-1, Side::Black),
+                _ => return Err(invalid()),
+            };
+            let pawn = square.offset(0, direction).ok_or_else(invalid)?;
+            let origin = square.offset(0, -direction).ok_or_else(invalid)?;
+            if position.board.piece_at(pawn) != Piece::Pawn || position.board.side_at(pawn) != pushed_side || position.board.piece_at(square) != Piece::None || position.board.piece_at(origin) != Piece::None {
+                return Err(invalid());
+            }
+            position.en_passant_square = Some(square);
+        }
+
+        if fields.len() == 6 {
+            position.halfmove_clock = fields[4].parse().map_err(|_| FenError::InvalidHalfmoveClock(fields[4].to_string()))?;
+            position.fullmove_number = fields[5]
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| FenError::InvalidFullmoveNumber(fields[5].to_string()))?;
+        }
+        position.hash = position.board.key() ^ position.hash_state();
+        Ok(position)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                let square = Square::new(file, rank).expect("rank and file are on the board");
+                let piece = self.board.piece_at(square);
+                if piece == Piece::None {
+                    empty += 1;
+                    continue;
+                }
+                if empty > 0 {
+                    placement.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                placement.push(piece_to_char(piece, self.board.side_at(square)));
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let side = if self.curr_turn == Side::Black { "b" } else { "w" };
+
+        let mut castling = String::new();
+        for (letter, right) in CASTLING_LETTERS {
+            if self.castling_rights.contains(right) {
+                castling.push(letter);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+
+        let en_passant = match self.en_passant_square() {
+            Some(square) => square.to_string(),
+            None => "-".to_string(),
+        };
+
+        format!(
+            "{} {} {} {} {} {}",
+            placement,
+            side,
+            castling,
+            en_passant,
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+}
+
+impl Game {
+    //Builds a game starting from a FEN string, see Position::from_fen.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        Ok(Game::from_position(Position::from_fen(fen)?))
+    }
+
+    pub fn to_fen(&self) -> String {
+        self.position().to_fen()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::bitboard::LIGHT_SQUARES;
+use crate::{Game, Piece, Position, Side};
+
+impl Position {
+    //Whether side could still checkmate by some series of legal moves, even with the opponent's help. Pieces of the opponent can block the mated king's escape squares, so a lone knight or bishop can mate unless the opponent has only bishops that can't block where it is needed.
+    pub fn has_mating_material(&self, side: Side) -> bool {
+        let board = &self.board;
+        if !(board.pieces(Piece::Pawn, side) | board.pieces(Piece::Rook, side) | board.pieces(Piece::Queen, side)).is_empty() {
+            return true;
+        }
+        let knights = board.pieces(Piece::Knight, side);
+        let bishops = board.pieces(Piece::Bishop, side);
+        if (knights | bishops).count() >= 2 && !knights.is_empty() {
+            return true;
+        }
+        if !(bishops & LIGHT_SQUARES).is_empty() && !(bishops & !LIGHT_SQUARES).is_empty() {
+            return true;
+        }
+
+        let opponent = !side;
+        let opponent_pieces = board.side_pieces(opponent) & !board.pieces(Piece::King, opponent);
+        if !knights.is_empty() {
+            return !opponent_pieces.is_empty();
+        }
+        if bishops.is_empty() {
+            return false;
+        }
+        //bishops that all stand on one colour only attack that colour, so the king's escape squares of the other colour have to be blocked
+        let bishop_colour = if (bishops & LIGHT_SQUARES).is_empty() { !LIGHT_SQUARES } else { LIGHT_SQUARES };
+        let same_colour_bishops = board.pieces(Piece::Bishop, opponent) & bishop_colour;
+        opponent_pieces != same_colour_bishops
+    }
+
+    //Neither side can checkmate any more, such as king against king or king and bishop against king.
+    pub(crate) fn is_dead_position(&self) -> bool {
+        !self.has_mating_material(Side::White) && !self.has_mating_material(Side::Black)
+    }
+}
+
+impl Game {
+    pub fn has_mating_material(&self, side: Side) -> bool {
+        self.position().has_mating_material(side)
+    }
+}
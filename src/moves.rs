@@ -0,0 +1,66 @@
+use std::fmt;
+
+use crate::{Piece, Square};
+
+//The pieces a pawn can promote to, in the order moves are generated.
+pub(crate) const PROMOTION_PIECES: [Piece; 4] = [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight];
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MoveKind {
+    Normal,
+    DoublePawnPush,
+    EnPassant,
+    KingsideCastle,
+    QueensideCastle,
+}
+
+//A move as generated by Game. "captured" is Piece::None for quiet moves, and "promotion" is only set for pawns reaching the last rank.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Move {
+    pub origin: Square,
+    pub target: Square,
+    pub piece: Piece,
+    pub captured: Piece,
+    pub promotion: Option<Piece>,
+    pub kind: MoveKind,
+}
+
+impl Move {
+    //A move described only by its squares and promotion, for Game::try_move to look up among the legal moves.
+    pub fn new(origin: Square, target: Square, promotion: Option<Piece>) -> Self {
+        Move { origin, target, piece: Piece::None, captured: Piece::None, promotion, kind: MoveKind::Normal }
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.captured != Piece::None
+    }
+
+    pub fn is_castle(&self) -> bool {
+        self.kind == MoveKind::KingsideCastle || self.kind == MoveKind::QueensideCastle
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveError {
+    NoPieceAtOrigin,
+    NotYourTurn,
+    IllegalTarget,
+    PromotionPieceRequired,
+    InvalidPromotionPiece,
+    GameOver,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoPieceAtOrigin => write!(f, "there is no piece on the origin square"),
+            MoveError::NotYourTurn => write!(f, "the piece belongs to the side not to move"),
+            MoveError::IllegalTarget => write!(f, "the piece can't legally move to the target square"),
+            MoveError::PromotionPieceRequired => write!(f, "a promotion piece has to be chosen"),
+            MoveError::InvalidPromotionPiece => write!(f, "pawns can only promote to a queen, rook, bishop or knight"),
+            MoveError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
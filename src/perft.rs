@@ -0,0 +1,58 @@
+use crate::{Game, Move, Position};
+
+//The move in coordinate notation such as "e2e4" or "e7e8q", which is what other perft tools print.
+fn coordinate_notation(mv: &Move) -> String {
+    let mut text = format!("{}{}", mv.origin, mv.target);
+    if let Some(promotion) = mv.promotion {
+        text.push_str(&crate::san::piece_letter(promotion).to_ascii_lowercase());
+    }
+    text
+}
+
+impl Position {
+    //Counts the leaf nodes of the legal move tree "depth" plies deep. Each move is made on a copy of the position.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut nodes = 0;
+        for mv in moves {
+            let mut after = *self;
+            after.do_move(mv);
+            nodes += after.perft(depth - 1);
+        }
+        nodes
+    }
+
+    //Same as perft, but also prints the node count below each legal move, to find where a move generator goes wrong.
+    pub fn perft_divide(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut nodes = 0;
+        for mv in self.legal_moves() {
+            let mut after = *self;
+            after.do_move(mv);
+            let count = after.perft(depth - 1);
+            println!("{}: {}", coordinate_notation(&mv), count);
+            nodes += count;
+        }
+        println!();
+        println!("Nodes searched: {}", nodes);
+        nodes
+    }
+}
+
+impl Game {
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.position().perft(depth)
+    }
+
+    pub fn perft_divide(&self, depth: u32) -> u64 {
+        self.position().perft_divide(depth)
+    }
+}
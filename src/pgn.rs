@@ -0,0 +1,325 @@
+use std::fmt;
+
+use crate::fen::{FenError, START_FEN};
+use crate::san::{move_to_san, parse_san, SanError};
+use crate::{BoardState, Game, Position, Side};
+
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+const MAX_LINE_LENGTH: usize = 80;
+
+fn result_token(state: &BoardState) -> Option<&'static str> {
+    match state {
+        BoardState::CheckMated(Side::White)
+        | BoardState::WhiteLoseByCheckMate
+        | BoardState::WhiteLoseByTime => Some("0-1"),
+        BoardState::CheckMated(Side::Black)
+        | BoardState::BlackLoseByCheckMate
+        | BoardState::BlackLoseByTime => Some("1-0"),
+        BoardState::DrawBy75MoveRule
+        | BoardState::DrawByFivefoldRepetition
+        | BoardState::DrawByInsufficientMaterial
+        | BoardState::DrawByTimeoutVsInsufficientMaterial
+        | BoardState::DrawByStaleMate => Some("1/2-1/2"),
+        _ => None,
+    }
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Game {
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag_name, _)| tag_name == name).map(|(_, value)| value.as_str())
+    }
+
+    //Result of the game as a PGN termination marker. A result decided on the board wins over a "Result" tag, which is only used for results the game can't know about, like resignations.
+    pub fn pgn_result(&self) -> String {
+        match result_token(&self.board_state) {
+            Some(token) => token.to_string(),
+            None => self.tag("Result").unwrap_or("*").to_string(),
+        }
+    }
+
+    pub fn to_pgn(&self) -> String {
+        let result = self.pgn_result();
+        let mut pgn = String::new();
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = if name == "Result" { result.as_str() } else { self.tag(name).unwrap_or(default) };
+            pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+        }
+        let start_fen = self.start_fen.as_deref().filter(|&fen| fen != START_FEN);
+        if let Some(fen) = start_fen {
+            pgn.push_str("[SetUp \"1\"]\n");
+            pgn.push_str(&format!("[FEN \"{}\"]\n", fen));
+        }
+        for (name, value) in &self.tags {
+            let is_written = SEVEN_TAG_ROSTER.iter().any(|(roster_name, _)| roster_name == name) || name == "SetUp" || name == "FEN";
+            if !is_written {
+                pgn.push_str(&format!("[{} \"{}\"]\n", name, escape_tag_value(value)));
+            }
+        }
+        pgn.push('\n');
+
+        let mut replay = match start_fen {
+            Some(fen) => Position::from_fen(fen).expect("start position was exported by to_fen"),
+            None => Position::new(),
+        };
+        let mut tokens = Vec::new();
+        for (ply, &mv) in self.move_history.iter().enumerate() {
+            let white_to_move = replay.curr_turn() == Side::White;
+            if white_to_move {
+                tokens.push(format!("{}.", replay.fullmove_number()));
+            } else if ply == 0 {
+                tokens.push(format!("{}...", replay.fullmove_number()));
+            }
+            tokens.push(move_to_san(&replay, mv));
+            replay.do_move(mv);
+        }
+        tokens.push(result);
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+                pgn.push('\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                pgn.push(' ');
+                line_length += 1;
+            }
+            line_length += token.len();
+            pgn.push_str(&token);
+        }
+        pgn.push('\n');
+        pgn
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgnErrorKind {
+    InvalidTag,
+    InvalidFen(FenError),
+    InvalidMove(SanError),
+    UnterminatedComment,
+    UnterminatedVariation,
+    NoGame,
+}
+
+//Error while reading PGN. "game" and "ply" are counted from 1, a ply of 0 means the error happened before the first move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnError {
+    pub game: usize,
+    pub ply: usize,
+    pub token: String,
+    pub kind: PgnErrorKind,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match &self.kind {
+            PgnErrorKind::InvalidTag => "invalid tag pair".to_string(),
+            PgnErrorKind::InvalidFen(error) => format!("invalid FEN tag: {}", error),
+            PgnErrorKind::InvalidMove(error) => error.to_string(),
+            PgnErrorKind::UnterminatedComment => "unterminated comment".to_string(),
+            PgnErrorKind::UnterminatedVariation => "unterminated variation".to_string(),
+            PgnErrorKind::NoGame => "no game found".to_string(),
+        };
+        write!(f, "game {}, ply {}, token '{}': {}", self.game, self.ply, self.token, reason)
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+const RESULT_TOKENS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+//Game being read. The Game itself is only created once the tags are known, since a FEN tag decides the start position.
+struct PgnReader {
+    number: usize,
+    tags: Vec<(String, String)>,
+    game: Option<Game>,
+    ply: usize,
+}
+
+impl PgnReader {
+    fn new(number: usize) -> Self {
+        PgnReader { number, tags: Vec::new(), game: None, ply: 0 }
+    }
+
+    fn error(&self, token: &str, kind: PgnErrorKind) -> PgnError {
+        PgnError { game: self.number, ply: self.ply, token: token.to_string(), kind }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.game.is_none()
+    }
+
+    fn game(&mut self) -> Result<&mut Game, PgnError> {
+        if self.game.is_none() {
+            let mut game = match self.tags.iter().find(|(name, _)| name == "FEN") {
+                Some((_, fen)) => Game::from_fen(fen).map_err(|error| self.error(fen, PgnErrorKind::InvalidFen(error)))?,
+                None => Game::new(),
+            };
+            game.tags = self.tags.clone();
+            self.game = Some(game);
+        }
+        Ok(self.game.as_mut().unwrap())
+    }
+
+    fn play(&mut self, san: &str) -> Result<(), PgnError> {
+        //a bad FEN tag is reported before the first move, at ply 0
+        self.game()?;
+        self.ply += 1;
+        let game = self.game.as_mut().expect("game is created above");
+        let mv = match parse_san(game.position(), san) {
+            Ok(mv) => mv,
+            Err(error) => return Err(self.error(san, PgnErrorKind::InvalidMove(error))),
+        };
+        game.do_move(mv);
+        Ok(())
+    }
+
+    fn finish(mut self, result: Option<&str>) -> Result<Game, PgnError> {
+        let game = self.game()?;
+        if let Some(result) = result {
+            if game.tag("Result").is_none() {
+                game.set_tag("Result", result);
+            }
+        }
+        Ok(self.game.unwrap())
+    }
+}
+
+//Index of the "]" closing the tag that starts at "start", or the last index if it isn't closed. Brackets and escaped quotes inside the value don't count.
+fn tag_end(chars: &[char], start: usize) -> usize {
+    let mut in_value = false;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if in_value => i += 1,
+            '"' => in_value = !in_value,
+            ']' if !in_value => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    chars.len() - 1
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.push(chars.next()?);
+        } else {
+            unescaped.push(c);
+        }
+    }
+    Some((name.to_string(), unescaped))
+}
+
+//Reads every game in a PGN file. Moves are replayed with do_move, so only legal games are accepted.
+pub fn parse_pgn(pgn: &str) -> Result<Vec<Game>, PgnError> {
+    let mut games = Vec::new();
+    let mut reader = PgnReader::new(1);
+    let chars: Vec<char> = pgn.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let line_start = i == 0 || chars[i - 1] == '\n';
+        if c.is_whitespace() {
+            i += 1;
+        } else if (c == '%' && line_start) || c == ';' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '[' {
+            let end = tag_end(&chars, i);
+            let line: String = chars[i..=end].iter().collect();
+            // a tag after the movetext of a game without a result marker starts the next game.
+            if reader.game.is_some() {
+                let number = reader.number;
+                games.push(reader.finish(None)?);
+                reader = PgnReader::new(number + 1);
+            }
+            let tag = parse_tag(&line).ok_or_else(|| reader.error(&line, PgnErrorKind::InvalidTag))?;
+            reader.tags.push(tag);
+            i = end + 1;
+        } else if c == '{' {
+            let end = (i..chars.len()).find(|&j| chars[j] == '}');
+            i = end.ok_or_else(|| reader.error("{", PgnErrorKind::UnterminatedComment))? + 1;
+        } else if c == '(' {
+            let mut depth = 0;
+            let start = i;
+            while i < chars.len() {
+                match chars[i] {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    '{' => i = (i..chars.len()).find(|&j| chars[j] == '}').unwrap_or(chars.len()),
+                    _ => {}
+                }
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            if depth != 0 {
+                let token: String = chars[start..].iter().take_while(|c| !c.is_whitespace()).collect();
+                return Err(reader.error(&token, PgnErrorKind::UnterminatedVariation));
+            }
+        } else {
+            let start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && !"{}()[];".contains(chars[i]) {
+                i += 1;
+            }
+            let token: String = chars[start..i].iter().collect();
+            if token.starts_with('$') || token == "e.p." {
+                continue;
+            }
+            if RESULT_TOKENS.contains(&token.as_str()) {
+                let number = reader.number;
+                games.push(reader.finish(Some(&token))?);
+                reader = PgnReader::new(number + 1);
+                continue;
+            }
+            // move numbers such as "12." or "12..." may be glued to the move that follows them.
+            let san = match token.find('.') {
+                Some(dot) if token[..dot].chars().all(|c| c.is_ascii_digit()) => token[dot..].trim_start_matches('.'),
+                _ => token.as_str(),
+            };
+            if !san.is_empty() {
+                reader.play(san)?;
+            }
+        }
+    }
+    if !reader.is_empty() {
+        games.push(reader.finish(None)?);
+    }
+    Ok(games)
+}
+
+impl Game {
+    //Reads the first game of a PGN string, which has to contain at least one tag or move.
+    pub fn from_pgn(pgn: &str) -> Result<Self, PgnError> {
+        parse_pgn(pgn)?.into_iter().next().ok_or_else(|| PgnReader::new(1).error("", PgnErrorKind::NoGame))
+    }
+}
@@ -0,0 +1,442 @@
+use crate::attacks::{between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks, rook_attacks};
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::moves::PROMOTION_PIECES;
+use crate::{zobrist, CastlingRights, Move, MoveKind, Piece, Side, Square};
+
+const INITIAL_BOARD_PIECES: [Piece; 64] = [
+    Piece::Rook, Piece::Knight, Piece::Bishop, Piece::Queen, Piece::King, Piece::Bishop, Piece::Knight, Piece::Rook,
+    Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn,
+    Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None,
+    Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None,
+    Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None,
+    Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None, Piece::None,
+    Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn, Piece::Pawn,
+    Piece::Rook, Piece::Knight, Piece::Bishop, Piece::Queen, Piece::King, Piece::Bishop, Piece::Knight, Piece::Rook,
+];
+
+const INITIAL_BOARD_SIDES: [Side; 64] = [
+    Side::White, Side::White, Side::White, Side::White, Side::White, Side::White, Side::White, Side::White,
+    Side::White, Side::White, Side::White, Side::White, Side::White, Side::White, Side::White, Side::White,
+    Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None,
+    Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None,
+    Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None,
+    Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None, Side::None,
+    Side::Black, Side::Black, Side::Black, Side::Black, Side::Black, Side::Black, Side::Black, Side::Black,
+    Side::Black, Side::Black, Side::Black, Side::Black, Side::Black, Side::Black,Side::Black, Side::Black
+];
+
+fn square(index: i8) -> Square{
+    Square::from_index(index as usize).expect("index on the board")
+}
+
+//What checks and pins leave a side, worked out once and shared by all of its pieces.
+struct MoveRestrictions{
+    checkers: Bitboard,
+    //squares pieces other than the king can move to: anywhere out of check, onto the checker or between it and the king in check
+    evasions: Bitboard,
+    pins: Vec<(Square, Bitboard)>,
+}
+
+//The chess position alone: pieces, side to move, castling rights, en passant square and the move counters, without any history.
+//It is Copy, so engines and analysis tools can make moves on copies of it, where a Game would clone its whole history.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Position{
+    pub(crate) board: Board,
+    pub(crate) curr_turn: Side,
+    pub(crate) castling_rights: CastlingRights,
+    //plies since the last capture or pawn move
+    pub(crate) halfmove_clock: u16,
+    pub(crate) fullmove_number: u16,
+    pub(crate) en_passant_square: Option<Square>,
+    pub(crate) hash: u64,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Position{
+    //The starting position.
+    pub fn new() -> Self {
+        let mut position = Self{
+            board: Board::from_arrays(&INITIAL_BOARD_PIECES, &INITIAL_BOARD_SIDES),
+            curr_turn: Side::White,
+            castling_rights: CastlingRights::ALL,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            en_passant_square: None,
+            hash: 0,
+        };
+        position.hash = position.board.key() ^ position.hash_state();
+        position
+    }
+
+    pub fn curr_turn(&self) -> Side{
+        self.curr_turn
+    }
+
+    //Zobrist key of the position, equal for positions with the same pieces, side to move, castling rights and en passant capture.
+    pub fn hash(&self) -> u64{
+        self.hash
+    }
+
+    //The part of the Zobrist key that isn't piece placement. The en passant file only counts if a pawn can actually capture there.
+    pub(crate) fn hash_state(&self) -> u64{
+        let mut key = zobrist::castling_key(self.castling_rights.bits());
+        if let Some(en_passant) = self.en_passant_square() {
+            if !(pawn_attacks(en_passant, !self.curr_turn) & self.board.pieces(Piece::Pawn, self.curr_turn)).is_empty() {
+                key ^= zobrist::en_passant_key(en_passant.file());
+            }
+        }
+        if self.curr_turn == Side::Black {
+            key ^= zobrist::SIDE_KEY;
+        }
+        key
+    }
+
+    //Pieces on the board, index 0 is a1, 1 is b1, 8 is a2 and so on.
+    pub fn board_pieces(&self) -> [Piece; 64]{
+        self.board.pieces_array()
+    }
+
+    //Sides of the pieces on the board, indexed like board_pieces.
+    pub fn board_pieces_sides(&self) -> [Side; 64]{
+        self.board.sides_array()
+    }
+
+    pub fn piece_at(&self, square: Square) -> Piece{
+        self.board.piece_at(square)
+    }
+
+    pub fn side_at(&self, square: Square) -> Side{
+        self.board.side_at(square)
+    }
+
+    //The square a pawn skipped with a double step on the last move, where an enemy pawn could capture it en passant. None after any other move.
+    pub fn en_passant_square(&self) -> Option<Square>{
+        self.en_passant_square
+    }
+
+    //Squares the pawn on position can push to, one step or two from its starting rank.
+    fn get_pawn_pushes(&self, position: Square, side: Side) -> Bitboard{
+        let direction = if side == Side::White { 1 } else { -1 };
+        let start_rank = if side == Side::White { 1 } else { 6 };
+        let occupied = self.board.occupied();
+        let mut targets = Bitboard::EMPTY;
+
+        if let Some(single) = position.offset(0, direction).filter(|&square| !occupied.contains(square)) {
+            targets |= Bitboard::from_square(single);
+            if let Some(double) = single.offset(0, direction).filter(|&square| position.rank() == start_rank && !occupied.contains(square)) {
+                targets |= Bitboard::from_square(double);
+            }
+        }
+        targets
+    }
+
+    //Pushes and captures of enemy pieces. En passant is left to get_position_legal_movements, which has to try it on a copy of the board.
+    fn get_pawn_possible_movements(&self, position: Square, side: Side) -> Bitboard{
+        self.get_pawn_pushes(position, side) | (pawn_attacks(position, side) & self.board.side_pieces(!side))
+    }
+
+    //Castling targets of the king, the caller makes sure side isn't in check.
+    fn add_castling_moves(&self, side: Side) -> Bitboard{
+        match side {
+            Side::White => self.add_white_castling_moves(),
+            Side::Black => self.add_black_castling_moves(),
+            Side::None => Bitboard::EMPTY,
+        }
+    }
+
+    fn add_white_castling_moves(&self) -> Bitboard{
+        let mut targets = Bitboard::EMPTY;
+        if self.castling_rights.contains(CastlingRights::WHITE_QUEENSIDE) && self.can_castle(&[square(2), square(3)], Side::White) && self.board.piece_at(square(1)) == Piece::None {
+            targets |= Bitboard::from_square(square(2));
+        }
+        if self.castling_rights.contains(CastlingRights::WHITE_KINGSIDE) && self.can_castle(&[square(5), square(6)], Side::White) {
+            targets |= Bitboard::from_square(square(6));
+        }
+        targets
+    }
+
+    fn add_black_castling_moves(&self) -> Bitboard{
+        let mut targets = Bitboard::EMPTY;
+        if self.castling_rights.contains(CastlingRights::BLACK_QUEENSIDE) && self.can_castle(&[square(58), square(59)], Side::Black) && self.board.piece_at(square(57)) == Piece::None {
+            targets |= Bitboard::from_square(square(58));
+        }
+        if self.castling_rights.contains(CastlingRights::BLACK_KINGSIDE) && self.can_castle(&[square(61), square(62)], Side::Black) {
+            targets |= Bitboard::from_square(square(62));
+        }
+        targets
+    }
+
+    //The king passes over and lands on the squares of path, which have to be empty and not attacked.
+    fn can_castle(&self, path: &[Square], side: Side) -> bool {
+        path.iter().all(|&p| self.board.piece_at(p) == Piece::None && self.attackers_of(p, !side).is_empty())
+    }
+
+    //Pieces of side that attack the square, whether or not moving there would be legal for them.
+    pub fn attackers_of(&self, square: Square, side: Side) -> Bitboard{
+        if side == Side::None {
+            return Bitboard::EMPTY;
+        }
+        self.board.attackers_to(square, side, self.board.occupied())
+    }
+
+    //How many pieces of side attack each square, indexed like board_pieces. Pawns count on the squares they capture on, not where they push to,
+    //and a square is attacked whether or not it is empty or which side is on it.
+    pub fn attack_map(&self, side: Side) -> [u8; 64]{
+        let mut counts = [0; 64];
+        if side == Side::None {
+            return counts;
+        }
+        for position in self.board.side_pieces(side) {
+            for target in self.board.attacks_from(position) {
+                counts[target.index()] += 1;
+            }
+        }
+        counts
+    }
+
+    //The squares attacked by at least one piece of side, attack_map as a set.
+    pub fn attacked_squares(&self, side: Side) -> Bitboard{
+        if side == Side::None {
+            return Bitboard::EMPTY;
+        }
+        self.board.side_pieces(side).fold(Bitboard::EMPTY, |attacked, position| attacked | self.board.attacks_from(position))
+    }
+
+    //Enemy pieces giving check to the side to move.
+    pub fn checkers(&self) -> Bitboard{
+        self.checkers_of(self.curr_turn)
+    }
+
+    fn checkers_of(&self, side: Side) -> Bitboard{
+        match self.board.king_square(side) {
+            Some(king) if side != Side::None => self.attackers_of(king, !side),
+            _ => Bitboard::EMPTY,
+        }
+    }
+
+    //Pieces of side that can't leave the line between their king and an enemy rook, bishop or queen without exposing the king.
+    pub fn pinned_pieces(&self, side: Side) -> Bitboard{
+        if side == Side::None {
+            return Bitboard::EMPTY;
+        }
+        self.board.pins(side).into_iter().fold(Bitboard::EMPTY, |pinned, (square, _)| pinned | Bitboard::from_square(square))
+    }
+
+    pub fn is_checked(&self, side: Side) -> bool{
+        !self.checkers_of(side).is_empty()
+    }
+
+    fn move_restrictions(&self, side: Side) -> MoveRestrictions{
+        let checkers = self.checkers_of(side);
+        let evasions = match (checkers.first(), self.board.king_square(side)) {
+            (Some(checker), Some(king)) => between(king, checker) | Bitboard::from_square(checker),
+            _ => !Bitboard::EMPTY,
+        };
+        MoveRestrictions{ checkers, evasions, pins: self.board.pins(side) }
+    }
+
+    //Legal moves of the piece on position. A king can't step onto an attacked square, other pieces can't move at all in double check,
+    //have to capture or block a single checker, and stay on their pin line.
+    fn get_position_legal_movements(&self, position: Square, restrictions: &MoveRestrictions, out: &mut Vec<Move>){
+        let side = self.board.side_at(position);
+        if side == Side::None {
+            return;
+        }
+        let occupied = self.board.occupied();
+        let own = self.board.side_pieces(side);
+        let piece = self.board.piece_at(position);
+        let mut targets = match piece {
+            Piece::Pawn => self.get_pawn_possible_movements(position, side),
+            Piece::Knight => knight_attacks(position),
+            Piece::Bishop => bishop_attacks(position, occupied),
+            Piece::Rook => rook_attacks(position, occupied),
+            Piece::Queen => queen_attacks(position, occupied),
+            Piece::King => king_attacks(position),
+            Piece::None => Bitboard::EMPTY,
+        } & !own;
+
+        if piece == Piece::King {
+            //the king doesn't block the ray of a slider attacking it, so it is taken off the board
+            let without_king = occupied ^ Bitboard::from_square(position);
+            for target in targets {
+                if !self.board.attackers_to(target, !side, without_king).is_empty() {
+                    targets ^= Bitboard::from_square(target);
+                }
+            }
+            if restrictions.checkers.is_empty() {
+                targets |= self.add_castling_moves(side);
+            }
+        }
+        else {
+            if restrictions.checkers.count() > 1 {
+                return;
+            }
+            targets &= restrictions.evasions;
+            if let Some(&(_, line)) = restrictions.pins.iter().find(|(pinned, _)| *pinned == position) {
+                targets &= line;
+            }
+            if let Some(en_passant) = self.en_passant_square.filter(|_| piece == Piece::Pawn && side == self.curr_turn) {
+                if pawn_attacks(position, side).contains(en_passant) && self.is_legal_en_passant(position, en_passant) {
+                    targets |= Bitboard::from_square(en_passant);
+                }
+            }
+        }
+        for target in targets {
+            self.push_moves_to_target(position, target, out);
+        }
+    }
+
+    //En passant takes two pawns off one rank, which can expose the king in ways pins don't cover, so it is tried on a copy of the board.
+    fn is_legal_en_passant(&self, origin: Square, target: Square) -> bool{
+        let side = self.board.side_at(origin);
+        let mut board = self.board;
+        board.apply_move(origin, target, None, self.en_passant_square);
+        !board.is_in_check(side)
+    }
+
+    pub fn get_position_possible_movements(&self, position: Square) -> Vec<Move>{
+        let mut to_return = Vec::new();
+        let restrictions = self.move_restrictions(self.board.side_at(position));
+        self.get_position_legal_movements(position, &restrictions, &mut to_return);
+        to_return
+    }
+
+    //Describes the move from origin to target, one move for each promotion piece if a pawn reaches the last rank.
+    fn push_moves_to_target(&self, origin: Square, target: Square, out: &mut Vec<Move>){
+        let mv = self.describe_move(origin, target);
+        if mv.piece == Piece::Pawn && (target.rank() == 0 || target.rank() == 7) {
+            for promotion in PROMOTION_PIECES {
+                out.push(Move { promotion: Some(promotion), ..mv });
+            }
+        }
+        else {
+            out.push(mv);
+        }
+    }
+
+    //The moving and captured piece and the kind of the move from origin to target, without a promotion.
+    pub(crate) fn describe_move(&self, origin: Square, target: Square) -> Move{
+        let piece = self.board.piece_at(origin);
+        let mut captured = self.board.piece_at(target);
+        let kind = if piece == Piece::King && origin.file().abs_diff(target.file()) == 2 {
+            if target.file() > origin.file() { MoveKind::KingsideCastle } else { MoveKind::QueensideCastle }
+        }
+        else if piece == Piece::Pawn && origin.rank().abs_diff(target.rank()) == 2 {
+            MoveKind::DoublePawnPush
+        }
+        else if piece == Piece::Pawn && self.en_passant_square == Some(target) {
+            captured = Piece::Pawn;
+            MoveKind::EnPassant
+        }
+        else {
+            MoveKind::Normal
+        };
+        Move { origin, target, piece, captured, promotion: None, kind }
+    }
+
+    //Every legal move of the side to move.
+    pub fn legal_moves(&self) -> Vec<Move>{
+        let mut moves = Vec::new();
+        let restrictions = self.move_restrictions(self.curr_turn);
+        for position in self.board.side_pieces(self.curr_turn) {
+            self.get_position_legal_movements(position, &restrictions, &mut moves);
+        }
+        moves
+    }
+
+    //Legal moves that capture a piece, including en passant.
+    pub fn legal_captures(&self) -> Vec<Move>{
+        let mut moves = self.legal_moves();
+        moves.retain(|mv| mv.is_capture());
+        moves
+    }
+
+    //Legal moves that don't capture anything, including castling and promotions to an empty square.
+    pub fn legal_quiet_moves(&self) -> Vec<Move>{
+        let mut moves = self.legal_moves();
+        moves.retain(|mv| !mv.is_capture());
+        moves
+    }
+
+    pub fn is_checkmate(&self) -> bool{
+        self.is_checked(self.curr_turn) && self.legal_moves().is_empty()
+    }
+
+    pub fn is_stalemate(&self) -> bool{
+        !self.is_checked(self.curr_turn) && self.legal_moves().is_empty()
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights{
+        self.castling_rights
+    }
+
+    //Rights without the king and rook on their starting squares are dropped, so the result can differ from "rights".
+    pub fn set_castling_rights(&mut self, rights: CastlingRights){
+        let old_state = self.hash_state();
+        self.castling_rights = CastlingRights::NONE;
+        for side in [Side::White, Side::Black] {
+            let rank = if side == Side::White { 0 } else { 7 };
+            let has_piece = |file: u8, piece: Piece| {
+                let square = Square::new(file, rank).expect("square on the first or last rank");
+                self.board.piece_at(square) == piece && self.board.side_at(square) == side
+            };
+            if !has_piece(4, Piece::King) {
+                continue;
+            }
+            for (rook_file, right) in [(7, CastlingRights::kingside(side)), (0, CastlingRights::queenside(side))] {
+                if rights.contains(right) && has_piece(rook_file, Piece::Rook) {
+                    self.castling_rights.insert(right);
+                }
+            }
+        }
+        self.hash ^= old_state ^ self.hash_state();
+    }
+
+    //Plies since the last capture or pawn move.
+    pub fn halfmove_clock(&self) -> u16{
+        self.halfmove_clock
+    }
+
+    //Starts at 1 and goes up after every move of Black.
+    pub fn fullmove_number(&self) -> u16{
+        self.fullmove_number
+    }
+
+    //Makes the move without checking that it is legal, so it should come from legal_moves. Panics if a pawn reaches the last rank without a queen, rook, bishop or knight to promote to.
+    pub fn do_move(&mut self, mv: Move){
+        let piece = self.board.piece_at(mv.origin);
+        let promotes = piece == Piece::Pawn && (mv.target.rank() == 0 || mv.target.rank() == 7);
+        assert!(
+            !promotes || mv.promotion.is_some_and(|promotion| PROMOTION_PIECES.contains(&promotion)),
+            "a pawn reaching the last rank has to promote to a queen, rook, bishop or knight"
+        );
+        let promotion = if promotes { mv.promotion } else { None };
+        if piece == Piece::Pawn || self.board.piece_at(mv.target) != Piece::None {
+            self.halfmove_clock = 0;
+        }
+        else {
+            self.halfmove_clock += 1;
+        }
+        let old_key = self.board.key() ^ self.hash_state();
+        self.board.apply_move(mv.origin, mv.target, promotion, self.en_passant_square);
+        //moving the king or a rook loses its rights, and so does capturing a rook on its starting square
+        self.castling_rights.remove(CastlingRights::lost_on(mv.origin) | CastlingRights::lost_on(mv.target));
+        self.en_passant_square = if piece == Piece::Pawn && mv.origin.rank().abs_diff(mv.target.rank()) == 2 {
+            Square::new(mv.origin.file(), (mv.origin.rank() + mv.target.rank()) / 2)
+        }
+        else {
+            None
+        };
+        if self.curr_turn == Side::Black {
+            self.fullmove_number += 1;
+        }
+        self.curr_turn = !self.curr_turn;
+        self.hash ^= old_key ^ self.board.key() ^ self.hash_state();
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt;
+
+use crate::{Game, Move, MoveKind, Piece, Position, Square};
+
+pub(crate) fn piece_letter(piece: Piece) -> &'static str {
+    match piece {
+        Piece::King => "K",
+        Piece::Queen => "Q",
+        Piece::Rook => "R",
+        Piece::Bishop => "B",
+        Piece::Knight => "N",
+        Piece::Pawn | Piece::None => "",
+    }
+}
+
+//Writes the move in standard algebraic notation. Has to be called before the move is made on the position.
+pub(crate) fn move_to_san(position: &Position, mv: Move) -> String {
+    let mut san = String::new();
+
+    if mv.is_castle() {
+        san.push_str(if mv.kind == MoveKind::KingsideCastle { "O-O" } else { "O-O-O" });
+    } else if mv.piece == Piece::Pawn {
+        if mv.is_capture() {
+            san.push_str(&mv.origin.to_string()[..1]);
+            san.push('x');
+        }
+        san.push_str(&mv.target.to_string());
+        if let Some(promotion) = mv.promotion {
+            san.push('=');
+            san.push_str(piece_letter(promotion));
+        }
+    } else {
+        san.push_str(piece_letter(mv.piece));
+        let rivals: Vec<Square> = position
+            .legal_moves()
+            .into_iter()
+            .filter(|rival| rival.origin != mv.origin && rival.piece == mv.piece && rival.target == mv.target)
+            .map(|rival| rival.origin)
+            .collect();
+        if !rivals.is_empty() {
+            let origin_square = mv.origin.to_string();
+            if rivals.iter().all(|square| square.file() != mv.origin.file()) {
+                san.push_str(&origin_square[..1]);
+            } else if rivals.iter().all(|square| square.rank() != mv.origin.rank()) {
+                san.push_str(&origin_square[1..]);
+            } else {
+                san.push_str(&origin_square);
+            }
+        }
+        if mv.is_capture() {
+            san.push('x');
+        }
+        san.push_str(&mv.target.to_string());
+    }
+
+    let mut after = *position;
+    after.do_move(mv);
+    if after.is_checkmate() {
+        san.push('#');
+    } else if after.is_checked(after.curr_turn()) {
+        san.push('+');
+    }
+    san
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SanError {
+    InvalidSyntax,
+    IllegalMove,
+    AmbiguousMove,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::InvalidSyntax => write!(f, "not a move in standard algebraic notation"),
+            SanError::IllegalMove => write!(f, "illegal move"),
+            SanError::AmbiguousMove => write!(f, "ambiguous move"),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+fn piece_from_letter(letter: char) -> Option<Piece> {
+    match letter {
+        'K' => Some(Piece::King),
+        'Q' => Some(Piece::Queen),
+        'R' => Some(Piece::Rook),
+        'B' => Some(Piece::Bishop),
+        'N' => Some(Piece::Knight),
+        _ => None,
+    }
+}
+
+//Finds the legal move described by a SAN string, returned as origin, target and promotion piece.
+pub(crate) fn parse_san(position: &Position, san: &str) -> Result<Move, SanError> {
+    let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+    // "e.p." may trail an en passant capture, with or without a space and before or after the check marker.
+    let (san, marked_en_passant) = match san.strip_suffix("e.p.") {
+        Some(stripped) => (stripped.trim_end().trim_end_matches(['+', '#']), true),
+        None => (san, false),
+    };
+
+    let castling = match san {
+        "O-O" | "0-0" => Some(true),
+        "O-O-O" | "0-0-0" => Some(false),
+        _ => None,
+    };
+    if let Some(kingside) = castling {
+        let kind = if kingside { MoveKind::KingsideCastle } else { MoveKind::QueensideCastle };
+        return position
+            .legal_moves()
+            .into_iter()
+            .find(|mv| mv.kind == kind)
+            .ok_or(SanError::IllegalMove);
+    }
+
+    let (body, promotion) = match san.split_once('=') {
+        Some((body, promotion)) => {
+            let mut letters = promotion.chars();
+            match (letters.next().and_then(piece_from_letter), letters.next()) {
+                (Some(piece), None) if piece != Piece::King => (body, Some(piece)),
+                _ => return Err(SanError::InvalidSyntax),
+            }
+        }
+        // the "=" is sometimes left out, as in "e8Q".
+        None => match san.chars().last().and_then(piece_from_letter) {
+            Some(piece) if piece != Piece::King && san.len() > 2 && san[..san.len() - 1].ends_with(['1', '8']) => (&san[..san.len() - 1], Some(piece)),
+            _ => (san, None),
+        },
+    };
+
+    let mut chars: Vec<char> = body.chars().collect();
+    let piece = match chars.first().copied().and_then(piece_from_letter) {
+        Some(piece) => {
+            chars.remove(0);
+            piece
+        }
+        None => Piece::Pawn,
+    };
+    if chars.len() < 2 {
+        return Err(SanError::InvalidSyntax);
+    }
+    let target_str: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let target: Square = target_str.parse().map_err(|_| SanError::InvalidSyntax)?;
+    let is_capture = chars.last() == Some(&'x');
+    if is_capture {
+        chars.pop();
+    }
+    let mut from_file = None;
+    let mut from_rank = None;
+    for c in chars {
+        match c {
+            'a'..='h' if from_file.is_none() && from_rank.is_none() => from_file = Some(c as u8 - b'a'),
+            '1'..='8' if from_rank.is_none() => from_rank = Some(c as u8 - b'1'),
+            _ => return Err(SanError::InvalidSyntax),
+        }
+    }
+
+    if piece == Piece::Pawn {
+        let reaches_last_rank = target.rank() == 0 || target.rank() == 7;
+        if is_capture != from_file.is_some() || from_rank.is_some() || reaches_last_rank != promotion.is_some() {
+            return Err(SanError::InvalidSyntax);
+        }
+    } else if promotion.is_some() || marked_en_passant {
+        return Err(SanError::InvalidSyntax);
+    }
+
+    let candidates: Vec<Move> = position
+        .legal_moves()
+        .into_iter()
+        .filter(|mv| {
+            mv.piece == piece
+                && from_file.is_none_or(|file| mv.origin.file() == file)
+                && from_rank.is_none_or(|rank| mv.origin.rank() == rank)
+                && mv.target == target
+                && mv.promotion == promotion
+                && (piece != Piece::Pawn || mv.is_capture() == is_capture)
+        })
+        .collect();
+    match candidates.len() {
+        0 => Err(SanError::IllegalMove),
+        1 if marked_en_passant && candidates[0].kind != MoveKind::EnPassant => Err(SanError::IllegalMove),
+        1 => Ok(candidates[0]),
+        _ => Err(SanError::AmbiguousMove),
+    }
+}
+
+impl Position {
+    //Writes a legal move of the side to move in standard algebraic notation, including disambiguation and check or mate suffixes.
+    pub fn to_san(&self, mv: Move) -> Result<String, SanError> {
+        if !self.legal_moves().contains(&mv) {
+            return Err(SanError::IllegalMove);
+        }
+        Ok(move_to_san(self, mv))
+    }
+
+    //Reads a move of the side to move written in standard algebraic notation.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        parse_san(self, san)
+    }
+}
+
+impl Game {
+    pub fn to_san(&self, mv: Move) -> Result<String, SanError> {
+        self.position().to_san(mv)
+    }
+
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        self.position().parse_san(san)
+    }
+}
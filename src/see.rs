@@ -0,0 +1,110 @@
+use crate::attacks::{bishop_attacks, rook_attacks};
+use crate::bitboard::Bitboard;
+use crate::{Game, Move, Piece, Position, Side};
+
+//Material value of each piece type, in centipawns by default. The king's value only has to be larger than everything it could win.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PieceValues {
+    pub pawn: i32,
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+    pub king: i32,
+}
+
+impl Default for PieceValues {
+    fn default() -> Self {
+        PieceValues { pawn: 100, knight: 320, bishop: 330, rook: 500, queen: 900, king: 20000 }
+    }
+}
+
+impl PieceValues {
+    pub fn value(&self, piece: Piece) -> i32 {
+        match piece {
+            Piece::King => self.king,
+            Piece::Queen => self.queen,
+            Piece::Rook => self.rook,
+            Piece::Bishop => self.bishop,
+            Piece::Knight => self.knight,
+            Piece::Pawn => self.pawn,
+            Piece::None => 0,
+        }
+    }
+}
+
+//Cheapest first, which is the order both sides recapture in.
+const RECAPTURE_ORDER: [Piece; 6] = [Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen, Piece::King];
+
+impl Position {
+    //Static exchange evaluation with the default piece values, see see_with_values.
+    pub fn see(&self, mv: Move) -> i32 {
+        self.see_with_values(mv, &PieceValues::default())
+    }
+
+    //Material the mover wins or loses if both sides keep recapturing on the target square with their cheapest piece, and each side may stop
+    //when going on would lose more. Rooks, bishops and queens lined up behind an attacker join in once it has moved. Pins are ignored.
+    pub fn see_with_values(&self, mv: Move, values: &PieceValues) -> i32 {
+        let board = &self.board;
+        let piece = board.piece_at(mv.origin);
+        let mut side = board.side_at(mv.origin);
+        if piece == Piece::None || mv.is_castle() {
+            return 0;
+        }
+        let target = mv.target;
+        let mut occupied = board.occupied() ^ Bitboard::from_square(mv.origin);
+        let mut gains = Vec::with_capacity(32);
+        if piece == Piece::Pawn && self.en_passant_square == Some(target) {
+            let captured = target.offset(0, if side == Side::White { -1 } else { 1 }).expect("square beside the pawn");
+            occupied ^= Bitboard::from_square(captured);
+            gains.push(values.pawn);
+        } else {
+            gains.push(values.value(board.piece_at(target)));
+        }
+        let promotes = piece == Piece::Pawn && (target.rank() == 0 || target.rank() == 7);
+        let mut on_target = if promotes { mv.promotion.unwrap_or(Piece::Queen) } else { piece };
+        if promotes {
+            gains[0] += values.value(on_target) - values.pawn;
+        }
+
+        let diagonal = board.pieces_of_type(Piece::Bishop) | board.pieces_of_type(Piece::Queen);
+        let straight = board.pieces_of_type(Piece::Rook) | board.pieces_of_type(Piece::Queen);
+        let mut attackers = (board.attackers_to(target, Side::White, occupied) | board.attackers_to(target, Side::Black, occupied)) & occupied;
+        loop {
+            side = !side;
+            let own = attackers & board.side_pieces(side);
+            let Some((attacker, square)) = RECAPTURE_ORDER
+                .into_iter()
+                .find_map(|piece| (own & board.pieces(piece, side)).first().map(|square| (piece, square)))
+            else {
+                break;
+            };
+            //the king can only take the last piece, when nothing can take it back
+            if attacker == Piece::King && !(attackers & board.side_pieces(!side)).is_empty() {
+                break;
+            }
+            gains.push(values.value(on_target) - gains[gains.len() - 1]);
+            on_target = attacker;
+            occupied ^= Bitboard::from_square(square);
+            attackers |= (bishop_attacks(target, occupied) & diagonal) | (rook_attacks(target, occupied) & straight);
+            attackers &= occupied;
+        }
+        //each side only makes its capture if that is better than stopping
+        while gains.len() > 1 {
+            let last = gains.pop().expect("more than one gain");
+            let previous = gains.last_mut().expect("more than one gain");
+            *previous = -(-*previous).max(last);
+        }
+        gains[0]
+    }
+}
+
+impl Game {
+    pub fn see(&self, mv: Move) -> i32 {
+        self.position().see(mv)
+    }
+
+    pub fn see_with_values(&self, mv: Move, values: &PieceValues) -> i32 {
+        self.position().see_with_values(mv, values)
+    }
+}
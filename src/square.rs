@@ -0,0 +1,83 @@
+use std::fmt;
+use std::str::FromStr;
+
+//A square on the board. Index 0 is a1, 1 is b1, 8 is a2 and so on up to 63 for h8, the same layout as board_pieces.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Square(u8);
+
+impl Square {
+    pub const fn new(file: u8, rank: u8) -> Option<Square> {
+        if file < 8 && rank < 8 {
+            Some(Square(rank * 8 + file))
+        } else {
+            None
+        }
+    }
+
+    pub const fn from_index(index: usize) -> Option<Square> {
+        if index < 64 {
+            Some(Square(index as u8))
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    //0 for the a file, 7 for the h file.
+    pub const fn file(self) -> u8 {
+        self.0 % 8
+    }
+
+    //0 for the first rank, 7 for the eighth rank.
+    pub const fn rank(self) -> u8 {
+        self.0 / 8
+    }
+
+    //The square the given number of files to the right and ranks up, or None if that is off the board.
+    pub fn offset(self, file_delta: i8, rank_delta: i8) -> Option<Square> {
+        let file = self.file() as i8 + file_delta;
+        let rank = self.rank() as i8 + rank_delta;
+        if (0..8).contains(&file) && (0..8).contains(&rank) {
+            Square::new(file as u8, rank as u8)
+        } else {
+            None
+        }
+    }
+
+    //Every square from a1 to h8.
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseSquareError(pub String);
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid square '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s.to_ascii_lowercase().into_bytes();
+        match bytes[..] {
+            [file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Ok(Square((rank - b'1') * 8 + (file - b'a'))),
+            _ => Err(ParseSquareError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file()) as char, (b'1' + self.rank()) as char)
+    }
+}
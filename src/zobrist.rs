@@ -0,0 +1,39 @@
+use crate::{Piece, Side, Square};
+
+const PIECE_KEYS_START: usize = 0;
+const CASTLING_KEYS_START: usize = 2 * 6 * 64;
+const EN_PASSANT_KEYS_START: usize = CASTLING_KEYS_START + 4;
+const SIDE_KEY_INDEX: usize = EN_PASSANT_KEYS_START + 8;
+
+//Random numbers from splitmix64 with a fixed seed, so keys are the same in every build and can be stored.
+const fn generate_keys() -> [u64; SIDE_KEY_INDEX + 1] {
+    let mut keys = [0; SIDE_KEY_INDEX + 1];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut i = 0;
+    while i < keys.len() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+const KEYS: [u64; SIDE_KEY_INDEX + 1] = generate_keys();
+
+pub(crate) const SIDE_KEY: u64 = KEYS[SIDE_KEY_INDEX];
+
+pub(crate) fn piece_key(piece: Piece, side: Side, square: Square) -> u64 {
+    KEYS[PIECE_KEYS_START + ((side as usize * 6 + piece as usize) * 64) + square.index()]
+}
+
+//"rights" has one bit per castling right, in the order K, Q, k, q.
+pub(crate) fn castling_key(rights: u8) -> u64 {
+    (0..4).filter(|bit| rights & (1 << bit) != 0).fold(0, |key, bit| key ^ KEYS[CASTLING_KEYS_START + bit])
+}
+
+pub(crate) fn en_passant_key(file: u8) -> u64 {
+    KEYS[EN_PASSANT_KEYS_START + file as usize]
+}
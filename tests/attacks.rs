@@ -0,0 +1,78 @@
+mod common;
+
+use caspervk_chess::{Bitboard, Piece, Side, Square, START_FEN};
+use common::{game, square, squares};
+
+#[test]
+fn checkers_are_the_pieces_giving_check() {
+    assert_eq!(game(START_FEN).checkers(), Bitboard::EMPTY);
+    let single = game("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1");
+    assert_eq!(single.checkers(), squares(&["e2"]));
+    assert!(single.is_checked(Side::White));
+}
+
+#[test]
+fn double_check_leaves_only_king_moves() {
+    let game = game("4k3/8/8/8/1b6/8/4r3/4K3 w - - 0 1");
+    assert_eq!(game.checkers(), squares(&["b4", "e2"]));
+    let moves = game.legal_moves();
+    assert!(!moves.is_empty());
+    assert!(moves.iter().all(|mv| mv.piece == Piece::King));
+}
+
+#[test]
+fn pinned_knight_can_not_move() {
+    let game = game("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1");
+    assert_eq!(game.pinned_pieces(Side::White), squares(&["e2"]));
+    assert_eq!(game.pinned_pieces(Side::Black), Bitboard::EMPTY);
+    assert!(game.get_position_possible_movements(square("e2")).is_empty());
+    // the pinned knight still attacks, and protects, its squares
+    assert!(game.attackers_of(square("c3"), Side::White).contains(square("e2")));
+}
+
+#[test]
+fn two_pieces_on_the_line_are_not_pinned() {
+    let game = game("4r1k1/8/8/8/8/4B3/4N3/4K3 w - - 0 1");
+    assert_eq!(game.pinned_pieces(Side::White), Bitboard::EMPTY);
+    assert_eq!(game.pinned_pieces(Side::None), Bitboard::EMPTY);
+}
+
+#[test]
+fn attackers_of_a_square() {
+    let game = game(START_FEN);
+    assert_eq!(game.attackers_of(square("f3"), Side::White), squares(&["e2", "g2", "g1"]));
+    assert_eq!(game.attackers_of(square("f6"), Side::Black), squares(&["e7", "g7", "g8"]));
+    // pawns attack diagonally, the square in front of them isn't attacked
+    assert_eq!(game.attackers_of(square("e4"), Side::White), Bitboard::EMPTY);
+    assert_eq!(game.attackers_of(square("f3"), Side::None), Bitboard::EMPTY);
+}
+
+#[test]
+fn starting_position_attack_map() {
+    let game = game(START_FEN);
+    let map = game.attack_map(Side::White);
+    // pawn pushes don't count, only the diagonal captures of the pawns and the knight jumps reach the third rank
+    let third_rank = [("a3", 2), ("b3", 2), ("c3", 3), ("d3", 2), ("e3", 2), ("f3", 3), ("g3", 2), ("h3", 2)];
+    for (name, count) in third_rank {
+        assert_eq!(map[square(name).index()], count, "{}", name);
+    }
+    assert!(map[24..].iter().all(|&count| count == 0));
+    // own pieces are attacked too
+    assert_eq!(map[square("d2").index()], 4);
+    assert_eq!(game.attack_map(Side::Black)[square("f6").index()], 3);
+    assert_eq!(game.attack_map(Side::None), [0; 64]);
+}
+
+#[test]
+fn attacked_squares_are_the_squares_of_the_attack_map() {
+    let game = game("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    for side in [Side::White, Side::Black] {
+        let map = game.attack_map(side);
+        let attacked = game.attacked_squares(side);
+        for index in 0..64u8 {
+            let square = Square::new(index % 8, index / 8).unwrap();
+            assert_eq!(attacked.contains(square), map[index as usize] > 0, "{:?} {}", side, square);
+        }
+    }
+    assert_eq!(game.attacked_squares(Side::None), Bitboard::EMPTY);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use caspervk_chess::{BoardState, Clock, Game, Increment, ManualTimeSource, Side, TimeControl, TimePeriod};
+use common::{game, play};
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+fn timed_game(fen: &str, control: TimeControl) -> (Game, Arc<ManualTimeSource>) {
+    let source = Arc::new(ManualTimeSource::new());
+    let mut game = game(fen);
+    game.set_clock(Clock::with_time_source(control, source.clone()));
+    (game, source)
+}
+
+#[test]
+fn fischer_increment_is_added_after_the_move() {
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::fischer(secs(60), secs(2)));
+    source.advance(secs(10));
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(50));
+    play(&mut game, "e4");
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(52));
+    assert_eq!(game.clock().unwrap().running(), Side::Black);
+}
+
+#[test]
+fn bronstein_delay_gives_back_at_most_the_time_used() {
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::bronstein(secs(60), secs(5)));
+    source.advance(secs(3));
+    play(&mut game, "e4");
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(60));
+    source.advance(secs(8));
+    play(&mut game, "e5");
+    assert_eq!(game.clock().unwrap().remaining(Side::Black), secs(57));
+}
+
+#[test]
+fn simple_delay_runs_before_the_clock() {
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::simple_delay(secs(60), secs(5)));
+    source.advance(secs(4));
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(60));
+    source.advance(secs(3));
+    play(&mut game, "e4");
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(58));
+}
+
+#[test]
+fn next_period_starts_after_its_move_count() {
+    let control = TimeControl::new(vec![
+        TimePeriod { moves: Some(2), time: secs(60), increment: Increment::None },
+        TimePeriod { moves: None, time: secs(30), increment: Increment::None },
+    ]);
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, control);
+    for san in ["Nf3", "Nf6", "Ng1"] {
+        source.advance(secs(10));
+        play(&mut game, san);
+    }
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(70));
+    assert_eq!(game.clock().unwrap().remaining(Side::Black), secs(50));
+}
+
+#[test]
+fn flag_fall_loses_the_game() {
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::sudden_death(secs(60)));
+    source.advance(secs(61));
+    assert_eq!(game.check_time(), BoardState::WhiteLoseByTime);
+    assert!(game.try_move(game.parse_san("e4").unwrap()).is_err());
+}
+
+#[test]
+fn flag_fall_against_a_lone_king_is_a_draw() {
+    let (mut game, source) = timed_game("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", TimeControl::sudden_death(secs(60)));
+    play(&mut game, "Ra7");
+    source.advance(secs(61));
+    assert_eq!(play(&mut game, "Kf8"), BoardState::BlackLoseByTime);
+
+    let (mut game, source) = timed_game("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", TimeControl::sudden_death(secs(60)));
+    source.advance(secs(61));
+    assert_eq!(play(&mut game, "Ra7"), BoardState::DrawByTimeoutVsInsufficientMaterial);
+}
+
+#[test]
+fn undo_gives_back_the_time_and_increment_of_the_move() {
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::fischer(secs(60), secs(10)));
+    for _ in 0..3 {
+        source.advance(secs(1));
+        play(&mut game, "e4");
+        game.undo_move();
+        assert_eq!(game.clock().unwrap().remaining(Side::White), secs(60));
+        assert_eq!(game.clock().unwrap().remaining(Side::Black), secs(60));
+        assert_eq!(game.clock().unwrap().running(), Side::White);
+    }
+}
+
+#[test]
+fn undo_takes_back_the_move_towards_the_next_period() {
+    let control = TimeControl::new(vec![
+        TimePeriod { moves: Some(2), time: secs(60), increment: Increment::None },
+        TimePeriod { moves: None, time: secs(30), increment: Increment::None },
+    ]);
+    let (mut game, source) = timed_game(caspervk_chess::START_FEN, control);
+    for san in ["Nf3", "Nf6", "Ng1"] {
+        source.advance(secs(10));
+        play(&mut game, san);
+    }
+    game.undo_move();
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(50));
+    source.advance(secs(10));
+    play(&mut game, "Ng1");
+    assert_eq!(game.clock().unwrap().remaining(Side::White), secs(70));
+}
+
+#[test]
+fn no_side_has_no_time() {
+    let (game, source) = timed_game(caspervk_chess::START_FEN, TimeControl::sudden_death(secs(60)));
+    source.advance(secs(61));
+    let clock = game.clock().unwrap();
+    assert_eq!(clock.remaining(Side::None), Duration::ZERO);
+    assert!(!clock.is_flagged(Side::None));
+    assert!(clock.is_flagged(Side::White));
+}
@@ -0,0 +1,26 @@
+// Helpers shared by the integration tests. Each test file only uses some of them.
+#![allow(dead_code)]
+
+use caspervk_chess::{Bitboard, BoardState, Game, Square};
+
+pub fn game(fen: &str) -> Game {
+    Game::from_fen(fen).unwrap()
+}
+
+// Plays the moves, given in SAN and separated by spaces, and returns the board state after the last one.
+pub fn play(game: &mut Game, moves: &str) -> BoardState {
+    let mut state = game.board_state();
+    for san in moves.split_whitespace() {
+        let mv = game.parse_san(san).unwrap();
+        state = game.do_move(mv);
+    }
+    state
+}
+
+pub fn square(name: &str) -> Square {
+    name.parse().unwrap()
+}
+
+pub fn squares(names: &[&str]) -> Bitboard {
+    names.iter().fold(Bitboard::EMPTY, |squares, &name| squares | Bitboard::from_square(square(name)))
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use caspervk_chess::{BoardState, Side};
+use common::{game, play};
+
+#[test]
+fn fivefold_repetition_ends_a_perpetual_check() {
+    let mut game = game("R3k3/8/8/8/8/8/8/4K2R b - - 0 1");
+    for _ in 0..3 {
+        assert_eq!(play(&mut game, "Kd7 Ra1 Ke8 Ra8+"), BoardState::Checked(Side::Black));
+    }
+    assert_eq!(game.repetition_count(), 4);
+    assert_eq!(play(&mut game, "Kd7 Ra1 Ke8 Ra8+"), BoardState::DrawByFivefoldRepetition);
+    assert_eq!(game.repetition_count(), 5);
+    assert!(game.board_state().is_game_over());
+}
+
+#[test]
+fn seventy_five_move_rule_ends_the_game_on_a_checking_move() {
+    let mut game = game("4k3/8/8/8/8/8/8/R3K3 w - - 149 80");
+    assert_eq!(play(&mut game, "Ra8+"), BoardState::DrawBy75MoveRule);
+}
+
+#[test]
+fn checkmate_on_the_seventy_fifth_move_still_counts() {
+    let mut game = game("k7/8/1K6/8/8/8/8/7R w - - 149 80");
+    assert_eq!(play(&mut game, "Rh8#"), BoardState::CheckMated(Side::Black));
+}
+
+#[test]
+fn fifty_move_rule_draw_has_to_be_claimed() {
+    let mut game = game("4k3/8/8/8/8/8/8/R3K3 w - - 98 80");
+    assert!(!game.request_draw(None));
+    play(&mut game, "Ra2");
+    assert!(!game.request_draw(None));
+    let hundredth = game.parse_san("Kd7").unwrap();
+    assert!(game.request_draw(Some(hundredth)));
+    assert_eq!(play(&mut game, "Kd7"), BoardState::Default);
+    assert_eq!(game.halfmove_clock(), 100);
+    assert!(game.request_draw(None));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use caspervk_chess::{Engine, Game, Move, Score, SearchLimits, SearchResult, START_FEN};
+use common::game;
+
+fn search(fen: &str, limits: SearchLimits) -> (Game, SearchResult) {
+    let game = game(fen);
+    let result = Engine::new().search(&game, limits);
+    (game, result)
+}
+
+fn pv_san(game: &Game, pv: &[Move]) -> Vec<String> {
+    let mut position = *game.position();
+    pv.iter()
+        .map(|&mv| {
+            let san = position.to_san(mv).unwrap();
+            position.do_move(mv);
+            san
+        })
+        .collect()
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let (game, result) = search("6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1", SearchLimits::depth(4));
+    assert_eq!(result.score, Score::Mate(1));
+    assert_eq!(pv_san(&game, &result.pv), ["Rd8#"]);
+    assert_eq!(result.best_move, result.pv.first().copied());
+}
+
+#[test]
+fn finds_mate_in_two_with_the_whole_line() {
+    let (game, result) = search("kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1", SearchLimits::depth(6));
+    assert_eq!(result.score, Score::Mate(2));
+    assert_eq!(pv_san(&game, &result.pv), ["Ra6", "bxa6", "b7#"]);
+}
+
+#[test]
+fn sees_being_mated() {
+    let (_, result) = search("7k/8/8/8/8/8/r7/1r5K w - - 0 1", SearchLimits::depth(3));
+    assert_eq!(result.score, Score::Mate(0));
+    assert_eq!(result.best_move, None);
+
+    let (_, result) = search("7k/8/8/8/8/1r6/r7/7K w - - 0 1", SearchLimits::depth(4));
+    assert_eq!(result.score, Score::Mate(-1));
+}
+
+#[test]
+fn wins_a_hanging_queen() {
+    let (game, result) = search("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1", SearchLimits::depth(2));
+    assert_eq!(pv_san(&game, &result.pv)[0], "Rxd5");
+}
+
+#[test]
+fn stops_at_the_node_limit_after_the_first_iteration() {
+    let (game, result) = search(START_FEN, SearchLimits::nodes(2000));
+    assert!(result.depth >= 1);
+    assert!(result.nodes <= 2000);
+    assert!(game.legal_moves().contains(&result.best_move.unwrap()));
+    assert_eq!(game.to_fen(), START_FEN);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use caspervk_chess::{FenError, Game, Position, Side, START_FEN};
+use common::square;
+
+#[test]
+fn fen_round_trips() {
+    for fen in [
+        START_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 12 40",
+        "r3k3/8/8/8/8/8/8/R3K2R b Kq - 3 17",
+    ] {
+        assert_eq!(Position::from_fen(fen).unwrap().to_fen(), fen);
+        assert_eq!(Game::from_fen(fen).unwrap().to_fen(), fen);
+    }
+}
+
+#[test]
+fn four_field_fen_starts_the_move_counters() {
+    let position = Position::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+    assert_eq!(position.to_fen(), "4k3/8/8/8/8/8/8/4K3 b - - 0 1");
+}
+
+#[test]
+fn malformed_fields_are_rejected() {
+    let cases = [
+        ("4k3/8/8/8/8/8/8/4K3 w -", FenError::WrongFieldCount(3)),
+        ("4k3/8/8/8/8/8/4K3 w - - 0 1", FenError::WrongRankCount(7)),
+        ("4k3/8/8/8/8/8/9/4K3 w - - 0 1", FenError::InvalidPiece('9')),
+        ("4k3/8/8/8/8/8/7/4K3 w - - 0 1", FenError::InvalidRankLength(2)),
+        ("4k3/8/8/8/8/8/8/4K3 x - - 0 1", FenError::InvalidSideToMove("x".to_string())),
+        ("4k3/8/8/8/8/8/8/4K3 w X - 0 1", FenError::InvalidCastling("X".to_string())),
+        ("4k3/8/8/8/8/8/8/4K3 w - e9 0 1", FenError::InvalidEnPassant("e9".to_string())),
+        ("4k3/8/8/8/8/8/8/4K3 w - e6 0 1", FenError::InvalidEnPassant("e6".to_string())),
+        ("4k3/8/8/8/8/8/8/4K3 w - - x 1", FenError::InvalidHalfmoveClock("x".to_string())),
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 0", FenError::InvalidFullmoveNumber("0".to_string())),
+    ];
+    for (fen, error) in cases {
+        assert_eq!(Position::from_fen(fen).unwrap_err(), error, "{}", fen);
+    }
+}
+
+#[test]
+fn each_side_needs_exactly_one_king() {
+    assert_eq!(Position::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").unwrap_err(), FenError::InvalidKingCount(Side::Black));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").unwrap_err(), FenError::InvalidKingCount(Side::White));
+}
+
+#[test]
+fn pawns_on_the_back_ranks_are_rejected() {
+    assert_eq!(Position::from_fen("4k2P/8/8/8/8/8/8/4K3 w - - 0 1").unwrap_err(), FenError::PawnOnBackRank(square("h8")));
+    assert_eq!(Position::from_fen("4k3/8/8/8/8/8/8/p3K3 w - - 0 1").unwrap_err(), FenError::PawnOnBackRank(square("a1")));
+}
+
+#[test]
+fn side_not_to_move_in_check_is_rejected() {
+    assert_eq!(Position::from_fen("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1").unwrap_err(), FenError::SideNotToMoveInCheck(Side::Black));
+    assert_eq!(Game::from_fen("4k3/8/8/8/8/8/4r3/4K3 b - - 0 1").err(), Some(FenError::SideNotToMoveInCheck(Side::White)));
+    // the side to move may be in check
+    assert!(Position::from_fen("4k3/4R3/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+}
+
+#[test]
+fn castling_rights_need_their_king_and_rook() {
+    for (fen, castling) in [
+        ("4k3/8/8/8/8/8/8/4K3 w KQkq - 0 1", "KQkq"),
+        ("r3k2r/8/8/8/8/8/8/R3K3 w K - 0 1", "K"),
+        ("r3k2r/8/8/8/8/8/8/1R2K2R w Qk - 0 1", "Qk"),
+        ("r3k2r/8/8/8/8/8/8/R2K3R w Q - 0 1", "Q"),
+        ("r3k2r/8/8/8/8/8/8/R3K2R w KQkr - 0 1", "KQkr"),
+    ] {
+        assert_eq!(Position::from_fen(fen).unwrap_err(), FenError::InvalidCastling(castling.to_string()), "{}", fen);
+    }
+    // the black queenside rook is missing, so that right can't be given
+    assert_eq!(Position::from_fen("4k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap_err(), FenError::InvalidCastling("KQkq".to_string()));
+    assert_eq!(Position::from_fen("4k2r/8/8/8/8/8/8/R3K2R w KQk - 0 1").unwrap().to_fen(), "4k2r/8/8/8/8/8/8/R3K2R w KQk - 0 1");
+}
+
+#[test]
+fn castling_letters_are_not_repeated_or_reordered() {
+    for castling in ["KKK", "KQkqK", "qK", "kK", ""] {
+        let fen = format!("r3k2r/8/8/8/8/8/8/R3K2R w {} - 0 1", castling);
+        let error = if castling.is_empty() { FenError::WrongFieldCount(5) } else { FenError::InvalidCastling(castling.to_string()) };
+        assert_eq!(Position::from_fen(&fen).unwrap_err(), error, "{}", fen);
+    }
+}
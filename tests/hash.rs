@@ -0,0 +1,66 @@
+mod common;
+
+use std::collections::HashSet;
+
+use caspervk_chess::{CastlingRights, Position, START_FEN};
+use common::{game, play};
+
+// The key kept up to date by do_move has to be the one computed from scratch for the same position.
+fn assert_hash_matches_fen(position: &Position) {
+    let fen = position.to_fen();
+    assert_eq!(position.hash(), Position::from_fen(&fen).unwrap().hash(), "{}", fen);
+}
+
+fn walk(position: &Position, depth: u32) {
+    assert_hash_matches_fen(position);
+    if depth == 0 {
+        return;
+    }
+    for mv in position.legal_moves() {
+        let mut after = *position;
+        after.do_move(mv);
+        walk(&after, depth - 1);
+    }
+}
+
+#[test]
+fn incremental_hash_matches_a_fresh_one_along_a_line() {
+    let mut game = game("r3k2r/1P6/8/8/3p4/8/4P3/R3K2R w KQkq - 0 1");
+    for san in ["O-O", "Kd7", "e4", "dxe3", "bxa8=Q", "Rxa8", "Rf7+"] {
+        play(&mut game, san);
+        assert_hash_matches_fen(game.position());
+    }
+}
+
+#[test]
+fn incremental_hash_matches_a_fresh_one_after_every_move() {
+    // castling, en passant and promotions all happen within two plies of these
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "4k3/1P6/8/2pP4/8/8/6p1/4K2R w K c6 0 1",
+    ] {
+        walk(&Position::from_fen(fen).unwrap(), 2);
+    }
+}
+
+#[test]
+fn hash_depends_on_the_side_to_move() {
+    let white = Position::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let black = Position::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1").unwrap();
+    assert_ne!(white.hash(), black.hash());
+}
+
+#[test]
+fn hash_depends_on_the_castling_rights() {
+    let hashes: HashSet<u64> = ["KQkq", "KQk", "Kkq", "K", "q", "-"]
+        .iter()
+        .map(|castling| Position::from_fen(&format!("r3k2r/8/8/8/8/8/8/R3K2R w {} - 0 1", castling)).unwrap().hash())
+        .collect();
+    assert_eq!(hashes.len(), 6);
+
+    let mut position = Position::from_fen(START_FEN).unwrap();
+    position.set_castling_rights(CastlingRights::WHITE_KINGSIDE);
+    assert_hash_matches_fen(&position);
+    assert_eq!(position.hash(), Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w K - 0 1").unwrap().hash());
+}
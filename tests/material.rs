@@ -0,0 +1,64 @@
+mod common;
+
+use caspervk_chess::{BoardState, Side};
+use common::game;
+
+fn is_dead(fen: &str) -> bool {
+    game(fen).board_state() == BoardState::DrawByInsufficientMaterial
+}
+
+#[test]
+fn lone_kings_are_dead() {
+    let game = game("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+    assert!(!game.has_mating_material(Side::White));
+    assert!(!game.has_mating_material(Side::Black));
+    assert_eq!(game.board_state(), BoardState::DrawByInsufficientMaterial);
+}
+
+#[test]
+fn lone_minor_piece_against_a_lone_king_is_dead() {
+    for fen in ["4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1"] {
+        assert!(!game(fen).has_mating_material(Side::White), "{}", fen);
+        assert!(is_dead(fen), "{}", fen);
+    }
+}
+
+#[test]
+fn bishops_on_the_same_colour_are_dead() {
+    // c1 and f8 are both dark squares
+    let fen = "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1";
+    assert!(!game(fen).has_mating_material(Side::White));
+    assert!(!game(fen).has_mating_material(Side::Black));
+    assert!(is_dead(fen));
+}
+
+#[test]
+fn bishops_on_opposite_colours_are_not_dead() {
+    // the bishop on g8 can block the king on its own colour while the one on c1 gives mate
+    let fen = "4k1b1/8/8/8/8/8/8/2B1K3 w - - 0 1";
+    assert!(game(fen).has_mating_material(Side::White));
+    assert!(game(fen).has_mating_material(Side::Black));
+    assert!(!is_dead(fen));
+}
+
+#[test]
+fn knight_can_mate_with_help_from_any_opposing_piece() {
+    let fen = "4kb2/8/8/8/8/8/8/1N2K3 w - - 0 1";
+    assert!(game(fen).has_mating_material(Side::White));
+    assert!(!is_dead(fen));
+}
+
+#[test]
+fn pawns_rooks_queens_and_two_minor_pieces_can_mate() {
+    for fen in [
+        "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1",
+        "4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1",
+        "4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1",
+    ] {
+        assert!(game(fen).has_mating_material(Side::White), "{}", fen);
+        assert!(!game(fen).has_mating_material(Side::Black), "{}", fen);
+        assert!(!is_dead(fen), "{}", fen);
+    }
+}
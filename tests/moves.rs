@@ -0,0 +1,95 @@
+mod common;
+
+use caspervk_chess::{BoardState, Game, Move, MoveError, Piece, Side, START_FEN};
+use common::{game, play, square};
+
+// Tries the move and checks that a rejected move leaves the game exactly as it was.
+fn try_move(game: &mut Game, origin: &str, target: &str, promotion: Option<Piece>) -> Result<BoardState, MoveError> {
+    let fen = game.to_fen();
+    let hash = game.hash();
+    let result = game.try_move(Move::new(square(origin), square(target), promotion));
+    if result.is_err() {
+        assert_eq!(game.to_fen(), fen);
+        assert_eq!(game.hash(), hash);
+    }
+    result
+}
+
+#[test]
+fn empty_origin_is_rejected() {
+    let mut game = game(START_FEN);
+    assert_eq!(try_move(&mut game, "e4", "e5", None), Err(MoveError::NoPieceAtOrigin));
+}
+
+#[test]
+fn opponents_piece_is_rejected() {
+    let mut game = game(START_FEN);
+    assert_eq!(try_move(&mut game, "e7", "e5", None), Err(MoveError::NotYourTurn));
+}
+
+#[test]
+fn illegal_target_is_rejected() {
+    let mut game = game(START_FEN);
+    assert_eq!(try_move(&mut game, "e2", "e5", None), Err(MoveError::IllegalTarget));
+    assert_eq!(try_move(&mut game, "g1", "e2", None), Err(MoveError::IllegalTarget));
+    // a move leaving the king in check
+    let mut pinned = common::game("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1");
+    assert_eq!(try_move(&mut pinned, "e2", "c3", None), Err(MoveError::IllegalTarget));
+}
+
+#[test]
+fn promotion_without_a_piece_is_rejected() {
+    let mut game = game("8/4P3/8/8/k7/8/8/4K3 w - - 0 1");
+    assert_eq!(try_move(&mut game, "e7", "e8", None), Err(MoveError::PromotionPieceRequired));
+}
+
+#[test]
+fn promotion_to_a_king_is_rejected() {
+    let mut game = game("8/4P3/8/8/k7/8/8/4K3 w - - 0 1");
+    assert_eq!(try_move(&mut game, "e7", "e8", Some(Piece::King)), Err(MoveError::InvalidPromotionPiece));
+}
+
+#[test]
+fn move_after_the_game_ended_is_rejected() {
+    let mut game = game(START_FEN);
+    play(&mut game, "f3 e5 g4 Qh4#");
+    assert_eq!(try_move(&mut game, "e2", "e4", None), Err(MoveError::GameOver));
+}
+
+#[test]
+fn legal_move_is_made() {
+    let mut game = game(START_FEN);
+    assert_eq!(try_move(&mut game, "e2", "e4", None), Ok(BoardState::Default));
+    assert_eq!(game.curr_turn(), Side::Black);
+    assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+}
+
+#[test]
+fn promotion_to_a_king_or_pawn_is_rejected() {
+    let mut game = game("3r4/4P3/8/8/k7/8/8/4K3 w - - 0 1");
+    for piece in [Piece::King, Piece::Pawn] {
+        assert_eq!(try_move(&mut game, "e7", "e8", Some(piece)), Err(MoveError::InvalidPromotionPiece));
+        assert_eq!(try_move(&mut game, "e7", "d8", Some(piece)), Err(MoveError::InvalidPromotionPiece));
+    }
+}
+
+#[test]
+fn each_promotion_piece_is_a_separate_move() {
+    let game = game("3r4/4P3/8/8/k7/8/8/4K3 w - - 0 1");
+    for target in ["e8", "d8"] {
+        let mut promotions: Vec<Piece> = game
+            .legal_moves()
+            .into_iter()
+            .filter(|mv| mv.origin == square("e7") && mv.target == square(target))
+            .map(|mv| mv.promotion.unwrap())
+            .collect();
+        promotions.sort_by_key(|&piece| piece as u8);
+        assert_eq!(promotions, [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight], "{}", target);
+    }
+    for piece in [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight] {
+        let mut game = game.clone();
+        assert!(try_move(&mut game, "e7", "d8", Some(piece)).is_ok());
+        assert_eq!(game.piece_at(square("d8")), piece);
+        assert_eq!(game.side_at(square("d8")), Side::White);
+    }
+}
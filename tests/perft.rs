@@ -0,0 +1,94 @@
+mod common;
+
+use common::game;
+
+//Node counts for depth 1, 2, ... from the position, compared with the published numbers.
+fn assert_perft(fen: &str, expected: &[u64]) {
+    let game = game(fen);
+    for (depth, &nodes) in (1..).zip(expected) {
+        assert_eq!(game.perft(depth), nodes, "perft({}) of {}", depth, fen);
+    }
+    assert_eq!(game.to_fen(), common::game(fen).to_fen(), "perft changed the game");
+}
+
+#[test]
+fn start_position() {
+    assert_perft("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &[20, 400, 8902, 197281, 4865609]);
+}
+
+#[test]
+fn kiwipete() {
+    assert_perft("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", &[48, 2039, 97862, 4085603]);
+}
+
+#[test]
+fn position_3() {
+    assert_perft("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", &[14, 191, 2812, 43238, 674624]);
+}
+
+#[test]
+fn position_4() {
+    assert_perft("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", &[6, 264, 9467, 422333]);
+}
+
+#[test]
+fn position_5() {
+    assert_perft("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8", &[44, 1486, 62379, 2103487]);
+}
+
+#[test]
+fn position_6() {
+    assert_perft("r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10", &[46, 2079, 89890, 3894594]);
+}
+
+//The positions below each test one rule, with the count published for the deepest depth only.
+fn assert_perft_at(fen: &str, depth: u32, expected: u64) {
+    let game = game(fen);
+    assert_eq!(game.perft(depth), expected, "perft({}) of {}", depth, fen);
+}
+
+#[test]
+fn en_passant_leaving_king_in_check() {
+    assert_perft_at("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6, 1134888);
+    assert_perft_at("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6, 1015133);
+}
+
+#[test]
+fn en_passant_giving_check() {
+    assert_perft_at("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1440467);
+}
+
+#[test]
+fn castling_giving_check() {
+    assert_perft_at("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661072);
+    assert_perft_at("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803711);
+}
+
+#[test]
+fn castling_rights_lost_by_captures() {
+    assert_perft_at("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1274206);
+}
+
+#[test]
+fn castling_through_attacked_squares() {
+    assert_perft_at("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1720476);
+}
+
+#[test]
+fn promotions() {
+    assert_perft_at("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3821001);
+    assert_perft_at("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217342);
+    assert_perft_at("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92683);
+}
+
+#[test]
+fn discovered_and_double_check() {
+    assert_perft_at("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1004658);
+    assert_perft_at("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23527);
+}
+
+#[test]
+fn stalemate_and_checkmate() {
+    assert_perft_at("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2217);
+    assert_perft_at("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567584);
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use caspervk_chess::{parse_pgn, BoardState, Clock, FenError, Game, ManualTimeSource, Move, PgnError, PgnErrorKind, SanError, Side, TimeControl};
+use common::{game, play, square};
+
+const TWO_GAMES: &str = r#"[Event "First"]
+[Result "1-0"]
+
+1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0
+
+[Event "Second"]
+[Result "1/2-1/2"]
+
+1. d4 {a comment} d5 (1... Nf6 2. c4) 2. c4 $1 1/2-1/2
+"#;
+
+const OPERA_GAME: &str = "e4 e5 Nf3 d6 d4 Bg4 dxe5 Bxf3 Qxf3 dxe5 Bc4 Nf6 Qb3 Qe7 Nc3 c6 Bg5 b5 Nxb5 cxb5 Bxb5+ Nbd7 O-O-O Rd8 Rxd7 Rxd7 Rd1 Qe6 Bxd7+ Nxd7 Qb8+ Nxb8 Rd8#";
+
+// The lines after the tags.
+fn movetext(pgn: &str) -> Vec<&str> {
+    pgn.split("\n\n").nth(1).unwrap().lines().collect()
+}
+
+#[test]
+fn every_game_of_a_file_is_read() {
+    let games = parse_pgn(TWO_GAMES).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].tag("Event"), Some("First"));
+    assert!(games[0].is_checkmate());
+    assert_eq!(games[1].tag("Event"), Some("Second"));
+    assert_eq!(games[1].to_fen(), "rnbqkbnr/ppp1pppp/8/3p4/2PP4/8/PP2PPPP/RNBQKBNR b KQkq c3 0 2");
+}
+
+#[test]
+fn from_pgn_reads_the_first_game() {
+    let game = Game::from_pgn(TWO_GAMES).unwrap();
+    assert_eq!(game.tag("Event"), Some("First"));
+    assert_eq!(game.pgn_result(), "1-0");
+}
+
+#[test]
+fn from_pgn_without_a_game_is_an_error() {
+    for pgn in ["", "  \n", "{just a comment}", "; a line comment\n"] {
+        let error = Game::from_pgn(pgn).err().unwrap();
+        assert_eq!(error.kind, PgnErrorKind::NoGame, "{:?}", pgn);
+    }
+    assert!(parse_pgn("{just a comment}").unwrap().is_empty());
+}
+
+#[test]
+fn illegal_move_names_the_game_ply_and_token() {
+    let pgn = format!("{}\n1. e4 e5 2. Ke3 *\n", TWO_GAMES);
+    let error = parse_pgn(&pgn).err().unwrap();
+    assert_eq!(error, PgnError { game: 3, ply: 3, token: "Ke3".to_string(), kind: PgnErrorKind::InvalidMove(SanError::IllegalMove) });
+}
+
+#[test]
+fn ambiguous_move_names_the_game_ply_and_token() {
+    let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/1N2KN2 w - - 0 1\"]\n\n1. Nd2 *\n";
+    let error = Game::from_pgn(pgn).err().unwrap();
+    assert_eq!(error, PgnError { game: 1, ply: 1, token: "Nd2".to_string(), kind: PgnErrorKind::InvalidMove(SanError::AmbiguousMove) });
+    assert!(Game::from_pgn(&pgn.replace("Nd2", "Nbd2")).is_ok());
+}
+
+#[test]
+fn new_game_exports_the_seven_tag_roster() {
+    let expected = "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"?\"]\n[Black \"?\"]\n[Result \"*\"]\n\n*\n";
+    assert_eq!(Game::new().to_pgn(), expected);
+    let mut game = Game::new();
+    game.set_tag("White", "Morphy");
+    game.set_tag("Opening", "Philidor Defense");
+    let pgn = game.to_pgn();
+    assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"Morphy\"]\n"));
+    assert!(pgn.contains("[Result \"*\"]\n[Opening \"Philidor Defense\"]\n\n"));
+}
+
+#[test]
+fn game_from_fen_exports_setup_and_fen_tags() {
+    let fen = "4k3/8/8/8/8/8/8/R3K3 b - - 0 40";
+    let mut game = game(fen);
+    play(&mut game, "Kd7 Ra7+");
+    let pgn = game.to_pgn();
+    assert!(pgn.contains(&format!("[Result \"*\"]\n[SetUp \"1\"]\n[FEN \"{}\"]\n\n", fen)));
+    assert_eq!(movetext(&pgn), ["40... Kd7 41. Ra7+ *"]);
+    assert!(!Game::new().to_pgn().contains("FEN"));
+}
+
+#[test]
+fn moves_made_with_move_new_are_exported_from_the_board() {
+    let mut game = game("r3k2r/8/8/8/8/8/8/R3K1NR w KQkq - 0 1");
+    game.do_move(Move::new(square("g1"), square("f3"), None));
+    game.do_move(Move::new(square("e8"), square("c8"), None));
+    game.do_move(Move::new(square("h1"), square("g1"), None));
+    game.do_move(Move::new(square("d8"), square("d1"), None));
+    assert_eq!(movetext(&game.to_pgn()), ["1. Nf3 O-O-O 2. Rg1 Rd1+ *"]);
+}
+
+#[test]
+fn result_comes_from_the_board() {
+    let mut mate = Game::new();
+    play(&mut mate, "f3 e5 g4 Qh4#");
+    assert_eq!(movetext(&mate.to_pgn()), ["1. f3 e5 2. g4 Qh4# 0-1"]);
+    assert!(mate.to_pgn().contains("[Result \"0-1\"]"));
+
+    let mut stalemate = game("k7/8/1Q6/8/8/8/8/7K w - - 0 1");
+    assert_eq!(play(&mut stalemate, "Kg2"), BoardState::DrawByStaleMate);
+    assert_eq!(movetext(&stalemate.to_pgn()), ["1. Kg2 1/2-1/2"]);
+
+    let source = Arc::new(ManualTimeSource::new());
+    let mut timed = Game::new();
+    timed.set_clock(Clock::with_time_source(TimeControl::sudden_death(Duration::from_secs(60)), source.clone()));
+    play(&mut timed, "e4");
+    source.advance(Duration::from_secs(61));
+    assert_eq!(play(&mut timed, "e5"), BoardState::BlackLoseByTime);
+    assert_eq!(movetext(&timed.to_pgn()), ["1. e4 1-0"]);
+    // a result the board doesn't know about, like a resignation, comes from the tag
+    let mut resigned = Game::new();
+    play(&mut resigned, "e4");
+    resigned.set_tag("Result", "1-0");
+    assert_eq!(movetext(&resigned.to_pgn()), ["1. e4 1-0"]);
+}
+
+#[test]
+fn long_movetext_is_wrapped_at_80_characters() {
+    let mut game = Game::new();
+    play(&mut game, OPERA_GAME);
+    let pgn = game.to_pgn();
+    let lines = movetext(&pgn);
+    assert!(lines.len() > 1);
+    assert!(lines.iter().all(|line| line.len() <= 80 && !line.starts_with(' ') && !line.ends_with(' ')));
+    assert_eq!(
+        lines.join(" "),
+        "1. e4 e5 2. Nf3 d6 3. d4 Bg4 4. dxe5 Bxf3 5. Qxf3 dxe5 6. Bc4 Nf6 7. Qb3 Qe7 8. Nc3 c6 9. Bg5 b5 10. Nxb5 cxb5 \
+         11. Bxb5+ Nbd7 12. O-O-O Rd8 13. Rxd7 Rxd7 14. Rd1 Qe6 15. Bxd7+ Nxd7 16. Qb8+ Nxb8 17. Rd8# 1-0"
+    );
+}
+
+#[test]
+fn exported_game_reads_back() {
+    let mut original = Game::new();
+    original.set_tag("White", "Paul Morphy");
+    original.set_tag("Black", "Duke Karl / Count Isouard");
+    play(&mut original, OPERA_GAME);
+    let pgn = original.to_pgn();
+    let read = Game::from_pgn(&pgn).unwrap();
+    assert_eq!(read.to_fen(), original.to_fen());
+    assert_eq!(read.board_state(), original.board_state());
+    assert_eq!(read.to_pgn(), pgn);
+
+    let mut from_fen = game("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 5 20");
+    play(&mut from_fen, "O-O Kd2");
+    let pgn = from_fen.to_pgn();
+    assert_eq!(Game::from_pgn(&pgn).unwrap().to_pgn(), pgn);
+}
+
+#[test]
+fn brackets_and_quotes_inside_tag_values_read_back() {
+    let game = Game::from_pgn("[Event \"x ] y\"]\n[Site \"a \\\"quoted]\\\" \\\\ name\"]\n\n1. e4 *\n").unwrap();
+    assert_eq!(game.tag("Event"), Some("x ] y"));
+    assert_eq!(game.tag("Site"), Some("a \"quoted]\" \\ name"));
+
+    let mut original = Game::new();
+    original.set_tag("Event", "Open [Round ] 2]");
+    original.set_tag("Annotator", "\"]\\");
+    play(&mut original, "e4 e5");
+    let pgn = original.to_pgn();
+    let read = Game::from_pgn(&pgn).unwrap();
+    assert_eq!(read.tag("Event"), Some("Open [Round ] 2]"));
+    assert_eq!(read.tag("Annotator"), Some("\"]\\"));
+    assert_eq!(read.to_pgn(), pgn);
+}
+
+#[test]
+fn invalid_fen_tag_is_reported_before_the_first_move() {
+    let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/4K3 x - - 0 1\"]\n\n1. Kd2 *\n";
+    let error = Game::from_pgn(pgn).err().unwrap();
+    assert_eq!(
+        error,
+        PgnError {
+            game: 1,
+            ply: 0,
+            token: "4k3/8/8/8/8/8/8/4K3 x - - 0 1".to_string(),
+            kind: PgnErrorKind::InvalidFen(FenError::InvalidSideToMove("x".to_string())),
+        }
+    );
+    // also when the game has no moves
+    let error = parse_pgn("[FEN \"8/8/8/8/8/8/8/4K3 w - - 0 1\"]\n\n*\n").err().unwrap();
+    assert_eq!((error.ply, error.kind), (0, PgnErrorKind::InvalidFen(FenError::InvalidKingCount(Side::Black))));
+}
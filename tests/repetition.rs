@@ -0,0 +1,85 @@
+mod common;
+
+use caspervk_chess::{MoveKind, START_FEN};
+use common::{game, play};
+
+#[test]
+fn repeated_position_is_counted() {
+    let mut game = game(START_FEN);
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "Nf3 Nf6 Ng1 Ng8");
+    assert_eq!(game.repetition_count(), 2);
+    play(&mut game, "Nf3 Nf6 Ng1 Ng8");
+    assert_eq!(game.repetition_count(), 3);
+    assert!(game.request_draw(None));
+    assert!(!game.board_state().is_game_over());
+}
+
+#[test]
+fn side_to_move_is_part_of_the_position() {
+    // the rook takes three moves to get back and the king two, so the pieces return with black to move
+    let mut game = game("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+    play(&mut game, "Ra2 Kd8 Ra3 Ke8 Ra1");
+    assert!(game.to_fen().starts_with("4k3/8/8/8/8/8/8/R3K3 b"));
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "Kd8 Ra2 Ke8 Ra3 Kd8 Ra1 Ke8");
+    assert!(game.to_fen().starts_with("4k3/8/8/8/8/8/8/R3K3 w"));
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn castling_rights_are_part_of_the_position() {
+    let mut game = game("rn2k2r/8/8/8/8/8/8/RN2K2R w KQkq - 0 1");
+    play(&mut game, "Nc3 Nc6 Nb1 Nb8");
+    assert_eq!(game.repetition_count(), 2);
+    // the pieces are back where they started, but the kingside castling rights are gone
+    play(&mut game, "Rg1 Rg8 Rh1 Rh8");
+    assert_eq!(game.to_fen(), "rn2k2r/8/8/8/8/8/8/RN2K2R w Qq - 8 5");
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "Nc3 Nc6 Nb1 Nb8");
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn capturable_en_passant_is_part_of_the_position() {
+    let mut game = game("4k3/5p2/8/4P3/8/8/8/R3K3 b - - 0 1");
+    play(&mut game, "f5");
+    assert!(game.legal_moves().iter().any(|mv| mv.kind == MoveKind::EnPassant));
+    play(&mut game, "Ra2 Kd8 Ra1 Ke8");
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "Ra2 Kd8 Ra1 Ke8");
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn en_passant_square_without_a_capture_is_ignored() {
+    let mut game = game("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    play(&mut game, "Nf6 Nf3 Ng8 Ng1");
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn irreversible_move_resets_the_count() {
+    let mut game = game(START_FEN);
+    play(&mut game, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1 Ng8");
+    assert_eq!(game.repetition_count(), 3);
+    play(&mut game, "e4");
+    assert_eq!(game.repetition_count(), 1);
+    play(&mut game, "e5 Nf3 Nf6 Ng1 Ng8");
+    assert_eq!(game.repetition_count(), 2);
+    assert!(!game.request_draw(None));
+}
+
+#[test]
+fn draw_can_be_claimed_with_the_move_that_repeats() {
+    let mut game = game(START_FEN);
+    play(&mut game, "Nf3 Nf6 Ng1 Ng8 Nf3 Nf6 Ng1");
+    assert!(!game.request_draw(None));
+    let fen = game.to_fen();
+    let repeating = game.parse_san("Ng8").unwrap();
+    let other = game.parse_san("Nh5").unwrap();
+    assert!(game.request_draw(Some(repeating)));
+    assert!(!game.request_draw(Some(other)));
+    // claiming doesn't make the move
+    assert_eq!(game.to_fen(), fen);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use caspervk_chess::{Game, Move, MoveKind, Piece, SanError, START_FEN};
+use common::{game, play, square};
+
+// Parses the SAN and checks that writing the move gives "written" back.
+fn parse_and_write(game: &Game, san: &str, written: &str) -> Move {
+    let mv = game.parse_san(san).unwrap();
+    assert_eq!(game.to_san(mv).unwrap(), written, "{}", san);
+    mv
+}
+
+#[test]
+fn knight_disambiguated_by_file() {
+    let game = game("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1");
+    let mv = parse_and_write(&game, "Nbd7", "Nbd7");
+    assert_eq!((mv.origin, mv.target), (square("b8"), square("d7")));
+    assert_eq!(parse_and_write(&game, "Nfd7", "Nfd7").origin, square("f6"));
+    assert_eq!(game.parse_san("Nd7"), Err(SanError::AmbiguousMove));
+}
+
+#[test]
+fn en_passant_capture() {
+    let game = game("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    let mv = parse_and_write(&game, "exd6 e.p.", "exd6");
+    assert_eq!(mv.kind, MoveKind::EnPassant);
+    assert_eq!(mv.captured, Piece::Pawn);
+    assert_eq!(game.parse_san("exd6"), Ok(mv));
+    assert_eq!(game.parse_san("exd6e.p."), Ok(mv));
+}
+
+#[test]
+fn castling() {
+    let game = game("r3k2r/8/8/8/8/8/8/4K3 b kq - 0 1");
+    assert_eq!(parse_and_write(&game, "O-O-O", "O-O-O").kind, MoveKind::QueensideCastle);
+    assert_eq!(parse_and_write(&game, "O-O", "O-O").kind, MoveKind::KingsideCastle);
+    assert_eq!(game.parse_san("0-0-0").unwrap().kind, MoveKind::QueensideCastle);
+    let no_rights = common::game("r3k2r/8/8/8/8/8/8/4K3 b - - 0 1");
+    assert_eq!(no_rights.parse_san("O-O"), Err(SanError::IllegalMove));
+}
+
+#[test]
+fn promotion_with_check() {
+    let game = game("8/4P3/8/8/k7/8/8/4K3 w - - 0 1");
+    let mv = parse_and_write(&game, "e8=Q+", "e8=Q+");
+    assert_eq!((mv.target, mv.promotion), (square("e8"), Some(Piece::Queen)));
+    // the "=" may be left out, and the check marker too
+    assert_eq!(game.parse_san("e8Q"), Ok(mv));
+    assert_eq!(game.parse_san("e8=Q"), Ok(mv));
+    assert_eq!(parse_and_write(&game, "e8=N", "e8=N").promotion, Some(Piece::Knight));
+    assert_eq!(game.parse_san("e8"), Err(SanError::InvalidSyntax));
+    assert_eq!(game.parse_san("e8=K"), Err(SanError::InvalidSyntax));
+    assert_eq!(game.parse_san("e8=P"), Err(SanError::InvalidSyntax));
+}
+
+#[test]
+fn rooks_disambiguated_by_file_and_by_rank() {
+    let by_file = game("4k3/8/8/8/8/8/4K3/R6R w - - 0 1");
+    assert_eq!(parse_and_write(&by_file, "Rad1", "Rad1").origin, square("a1"));
+    assert_eq!(parse_and_write(&by_file, "Rhd1", "Rhd1").origin, square("h1"));
+    assert_eq!(by_file.parse_san("Rd1"), Err(SanError::AmbiguousMove));
+
+    let by_rank = game("4k3/8/8/R7/8/8/4K3/R7 w - - 0 1");
+    assert_eq!(parse_and_write(&by_rank, "R1a3", "R1a3").origin, square("a1"));
+    assert_eq!(parse_and_write(&by_rank, "R5a3", "R5a3").origin, square("a5"));
+    assert_eq!(by_rank.parse_san("Ra3"), Err(SanError::AmbiguousMove));
+}
+
+#[test]
+fn three_queens_disambiguated_by_file_rank_or_both() {
+    // the queens on e4, h4 and h1 can all go to e1
+    let game = game("8/k7/8/8/4Q2Q/8/8/K6Q w - - 0 1");
+    assert_eq!(parse_and_write(&game, "Qee1", "Qee1").origin, square("e4"));
+    assert_eq!(parse_and_write(&game, "Q1e1", "Q1e1").origin, square("h1"));
+    assert_eq!(parse_and_write(&game, "Qh4e1", "Qh4e1").origin, square("h4"));
+    for ambiguous in ["Qe1", "Qhe1", "Q4e1"] {
+        assert_eq!(game.parse_san(ambiguous), Err(SanError::AmbiguousMove), "{}", ambiguous);
+    }
+}
+
+#[test]
+fn checkmate_gets_a_hash() {
+    let mut game = game(START_FEN);
+    play(&mut game, "f3 e5 g4");
+    let mate = parse_and_write(&game, "Qh4#", "Qh4#");
+    assert_eq!(game.parse_san("Qh4"), Ok(mate));
+}
+
+#[test]
+fn illegal_and_malformed_moves_are_rejected() {
+    let game = game(START_FEN);
+    assert_eq!(game.parse_san("Nf6"), Err(SanError::IllegalMove));
+    assert_eq!(game.parse_san("e5"), Err(SanError::IllegalMove));
+    assert_eq!(game.parse_san("Ke2"), Err(SanError::IllegalMove));
+    assert_eq!(game.parse_san("exd3"), Err(SanError::IllegalMove));
+    for malformed in ["", "Z", "Nz3", "e9", "e4=Q", "Nf3=Q", "O-O-O-O"] {
+        assert_eq!(game.parse_san(malformed), Err(SanError::InvalidSyntax), "{:?}", malformed);
+    }
+    let knight_backwards = Move::new(square("g1"), square("g3"), None);
+    assert_eq!(game.to_san(knight_backwards), Err(SanError::IllegalMove));
+}
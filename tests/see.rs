@@ -0,0 +1,41 @@
+mod common;
+
+use caspervk_chess::PieceValues;
+use common::game;
+
+fn see(fen: &str, san: &str) -> i32 {
+    let game = game(fen);
+    let mv = game.parse_san(san).unwrap();
+    game.see(mv)
+}
+
+#[test]
+fn undefended_pawn_is_won() {
+    assert_eq!(see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "Rxe5"), 100);
+}
+
+#[test]
+fn pieces_behind_attackers_join_the_exchange() {
+    // the queen behind the bishop on f6 and the one behind the rook on e2 both take part, and the knight is lost for a pawn
+    assert_eq!(see("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1", "Nxe5"), 100 - 320);
+}
+
+#[test]
+fn defended_piece_is_only_won_by_a_cheaper_attacker() {
+    assert_eq!(see("4k3/8/3p4/4n3/3P4/8/8/4K3 w - - 0 1", "dxe5"), 320 - 100);
+    assert_eq!(see("4k3/8/3p4/4n3/8/8/7Q/4K3 w - - 0 1", "Qxe5"), 320 - 900);
+    assert_eq!(see("4k3/8/3p4/4n3/8/5N2/8/4K3 w - - 0 1", "Nxe5"), 0);
+}
+
+#[test]
+fn quiet_move_to_an_attacked_square_loses_the_piece() {
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/4KB2 w - - 0 1", "Bc4"), -330);
+}
+
+#[test]
+fn piece_values_can_be_changed() {
+    let game = game("4k3/8/3p4/4n3/8/8/7Q/4K3 w - - 0 1");
+    let mv = game.parse_san("Qxe5").unwrap();
+    let values = PieceValues { queen: 300, ..PieceValues::default() };
+    assert_eq!(game.see_with_values(mv, &values), 320 - 300);
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use caspervk_chess::{BoardState, CastlingRights, Game, Side, Square};
+use common::game;
+
+#[derive(Debug, PartialEq)]
+struct Snapshot {
+    fen: String,
+    hash: u64,
+    castling_rights: CastlingRights,
+    halfmove_clock: u16,
+    board_state: BoardState,
+    last_move: (Option<Square>, Option<Square>),
+}
+
+fn snapshot(game: &Game) -> Snapshot {
+    Snapshot {
+        fen: game.to_fen(),
+        hash: game.hash(),
+        castling_rights: game.castling_rights(),
+        halfmove_clock: game.halfmove_clock(),
+        board_state: game.board_state(),
+        last_move: (game.last_move_origin, game.last_move_target),
+    }
+}
+
+#[test]
+fn undo_restores_every_position_back_to_the_start() {
+    let mut game = game("r3k2r/1P6/8/8/3p4/8/4P3/R3K2R w KQkq - 3 1");
+    // castling, a rook move losing a right, a double push, an en passant capture, a capture that underpromotes, a check and a king capture
+    let line = ["O-O", "Rh7", "e4", "dxe3", "bxa8=N", "Rh1+", "Kxh1"];
+    let mut before = Vec::new();
+    let mut played = Vec::new();
+    for san in line {
+        let mv = game.parse_san(san).unwrap();
+        before.push(snapshot(&game));
+        played.push(mv);
+        game.do_move(mv);
+    }
+    assert_eq!(game.to_fen(), "N3k3/8/8/8/8/4p3/8/R4R1K b - - 0 4");
+    assert_eq!(before[6].board_state, BoardState::Checked(Side::White));
+
+    while let Some(expected) = before.pop() {
+        assert_eq!(game.undo_move(), played.pop());
+        assert_eq!(snapshot(&game), expected);
+    }
+    assert_eq!(game.undo_move(), None);
+    assert_eq!(game.to_fen(), "r3k2r/1P6/8/8/3p4/8/4P3/R3K2R w KQkq - 3 1");
+}